@@ -3,12 +3,32 @@ use std::collections::HashMap;
 
 use game_data::GameData;
 
-const DATA_FILES: [&str; 2] = ["player", "npcs"];
+const DATA_FILES: [&str; 6] = ["tags", "synergies", "player", "npcs", "events", "villages"];
+// Wasm builds can't list directories - every script file has to be added here
+#[cfg(target_arch = "wasm32")]
+const SCRIPT_FILES: [&str; 15] = [
+    "prelude",
+    "alchemist",
+    "apothecary",
+    "bard",
+    "beggar",
+    "clay_statue",
+    "druid",
+    "mime",
+    "necromancer",
+    "poltergeist",
+    "shopkeeper",
+    "taxman",
+    "thief",
+    "vampire",
+    "vortex",
+];
 const SPRITE_DATA: &str = include_str!("../../../assets/sprites/units.json");
 
 #[derive(Default)]
 pub struct DataAssets {
     pub files: HashMap<String, ResourceId>,
+    pub scripts: HashMap<String, ResourceId>,
 }
 
 pub fn load_assets(context: &mut Context) {
@@ -203,9 +223,39 @@ pub fn load_data_assets(context: &mut Context) -> DataAssets {
         let resource_id = store.load(&path).expect(&path);
         assets.files.insert(name.to_string(), resource_id);
     }
+    for name in get_script_files() {
+        let path = format!("scripts/{}.rn", name);
+        let resource_id = store.load(&path).expect(&path);
+        assets.scripts.insert(name.to_string(), resource_id);
+    }
     assets
 }
 
+#[cfg(target_arch = "wasm32")]
+fn get_script_files() -> Vec<String> {
+    SCRIPT_FILES.iter().map(|a| a.to_string()).collect()
+}
+
+/// Names of all the `.rn` files in the scripts directory.
+#[cfg(not(target_arch = "wasm32"))]
+fn get_script_files() -> Vec<String> {
+    let root = std::env::var("ROGALIK_ASSETS").unwrap_or_else(|_| "assets".to_string());
+    let dir = std::path::Path::new(&root).join("scripts");
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        log::error!("Can't read the script directory {:?}!", dir);
+        return Vec::new();
+    };
+    let mut names = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "rn"))
+        .filter_map(|p| Some(p.file_stem()?.to_str()?.to_string()))
+        .collect::<Vec<_>>();
+    // Stable load order
+    names.sort();
+    names
+}
+
 pub fn load_data(
     assets: &DataAssets,
     data: &mut GameData,
@@ -229,6 +279,21 @@ pub fn load_data(
         store.mark_read(*v);
     }
 
+    for (k, v) in assets.scripts.iter() {
+        let Some(asset) = store.get(*v) else {
+            continue;
+        };
+        if reload && asset.state != AssetState::Updated {
+            continue;
+        }
+        updated = true;
+        match std::str::from_utf8(asset.data.get()) {
+            Ok(s) => data.add_script(k, s),
+            Err(_) => log::error!("Can't parse script {} as string!", k),
+        }
+        store.mark_read(*v);
+    }
+
     if updated {
         let units_data = game_data::sprites::load_sprite_sheet_data(SPRITE_DATA);
         game_data::sprites::update_sprite_data(data, &units_data);
@@ -242,5 +307,11 @@ pub fn load_data_item(name: &str, data: &[u8], game_data: &mut GameData) {
         log::error!("Can't parse {} as string!", name);
         return;
     };
-    game_data.add_entities(&s, name);
+    match name {
+        "events" => game_data.add_events(&s),
+        "villages" => game_data.add_villages(&s),
+        "tags" => game_data.add_tags(&s),
+        "synergies" => game_data.add_synergies(&s),
+        _ => game_data.add_entities(&s, name),
+    }
 }
@@ -1,9 +1,100 @@
-use rogalik::{input::MouseButton, prelude::*};
+use gilrs::{Button, EventType, Gilrs};
+use rogalik::{
+    input::{KeyCode, MouseButton, TouchPhase},
+    prelude::*,
+};
 
 use game_graphics::input::{ButtonState, InputState};
 
-pub fn get_input_state(camera: ResourceId, context: &Context) -> InputState {
+const DIGIT_KEYS: [KeyCode; 9] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+const DIR_KEYS: [(KeyCode, KeyCode, Vector2i); 4] = [
+    (KeyCode::ArrowUp, KeyCode::KeyW, Vector2i { x: 0, y: 1 }),
+    (KeyCode::ArrowDown, KeyCode::KeyS, Vector2i { x: 0, y: -1 }),
+    (KeyCode::ArrowLeft, KeyCode::KeyA, Vector2i { x: -1, y: 0 }),
+    (KeyCode::ArrowRight, KeyCode::KeyD, Vector2i { x: 1, y: 0 }),
+];
+
+const DIR_BUTTONS: [(Button, Vector2i); 4] = [
+    (Button::DPadUp, Vector2i { x: 0, y: 1 }),
+    (Button::DPadDown, Vector2i { x: 0, y: -1 }),
+    (Button::DPadLeft, Vector2i { x: -1, y: 0 }),
+    (Button::DPadRight, Vector2i { x: 1, y: 0 }),
+];
+
+// Seconds
+const LONG_PRESS_TIME: f32 = 0.5;
+// Physical pixels
+const DRAG_THRESH: f32 = 16.;
+
+/// Tracks the primary touch across frames.
+#[derive(Default)]
+pub struct TouchState {
+    origin: Vector2f,
+    held: f32,
+    dragging: bool,
+    long_pressed: bool,
+}
+
+/// Buttons pressed on any connected gamepad since the last frame.
+/// Rogalik only handles the keyboard, mouse and touch, so gamepads are polled here.
+pub struct GamepadState {
+    // None if the platform has no gamepad support
+    gilrs: Option<Gilrs>,
+    pressed: Vec<Button>,
+}
+impl Default for GamepadState {
+    fn default() -> Self {
+        let gilrs = match Gilrs::new() {
+            Ok(gilrs) => Some(gilrs),
+            Err(e) => {
+                log::warn!("Gamepad input unavailable: {}", e);
+                None
+            }
+        };
+        Self {
+            gilrs,
+            pressed: Vec::new(),
+        }
+    }
+}
+impl GamepadState {
+    fn update(&mut self) {
+        self.pressed.clear();
+        let Some(gilrs) = self.gilrs.as_mut() else {
+            return;
+        };
+        while let Some(event) = gilrs.next_event() {
+            if let EventType::ButtonPressed(button, _) = event.event {
+                self.pressed.push(button);
+            }
+        }
+    }
+    fn is_pressed(&self, button: Button) -> bool {
+        self.pressed.contains(&button)
+    }
+}
+
+pub fn get_input_state(
+    camera: ResourceId,
+    touch: &mut TouchState,
+    gamepad: &mut GamepadState,
+    context: &Context,
+) -> InputState {
+    gamepad.update();
+
     let mut click = ButtonState::Up;
+    let mut inspect = context.input.is_mouse_button_released(MouseButton::Right);
 
     if context.input.is_mouse_button_down(MouseButton::Left) {
         click = ButtonState::Down
@@ -15,14 +106,66 @@ pub fn get_input_state(camera: ResourceId, context: &Context) -> InputState {
         click = ButtonState::Pressed
     }
 
-    let m = context.input.get_mouse_physical_position();
+    let mut m = context.input.get_mouse_physical_position();
+
+    // Touch input overrides the mouse, as browsers also emulate mouse events
+    if let Some(t) = context.input.get_touches().values().next() {
+        m = t.position;
+        click = match t.phase {
+            TouchPhase::Started => {
+                *touch = TouchState {
+                    origin: t.position,
+                    ..Default::default()
+                };
+                ButtonState::Pressed
+            }
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                // Long press has already been handled as inspect
+                if touch.long_pressed {
+                    ButtonState::Up
+                } else {
+                    ButtonState::Released
+                }
+            }
+            _ => {
+                touch.held += context.time.get_delta();
+                touch.dragging |= (t.position - touch.origin).len() > DRAG_THRESH;
+                if !touch.dragging && !touch.long_pressed && touch.held >= LONG_PRESS_TIME {
+                    touch.long_pressed = true;
+                    inspect = true;
+                }
+                ButtonState::Down
+            }
+        };
+    }
+
     let mut w = Vector2f::ZERO;
     if let Some(camera) = context.graphics.get_camera(camera) {
         w = camera.camera_to_world(m);
     }
 
+    let dir = DIR_KEYS
+        .iter()
+        .find(|(a, b, _)| context.input.is_key_pressed(*a) || context.input.is_key_pressed(*b))
+        .map(|(_, _, d)| *d)
+        .or_else(|| {
+            DIR_BUTTONS
+                .iter()
+                .find(|(b, _)| gamepad.is_pressed(*b))
+                .map(|(_, d)| *d)
+        });
+
     InputState {
         click,
         mouse_world_position: w,
+        inspect,
+        dir,
+        digit: DIGIT_KEYS
+            .iter()
+            .position(|k| context.input.is_key_pressed(*k)),
+        action: context.input.is_key_pressed(KeyCode::Enter) || gamepad.is_pressed(Button::South),
+        fight: context.input.is_key_pressed(KeyCode::Space) || gamepad.is_pressed(Button::Start),
+        undo: context.input.is_key_pressed(KeyCode::KeyZ) || gamepad.is_pressed(Button::West),
+        debug: context.input.is_key_pressed(KeyCode::F1),
     }
 }
@@ -15,6 +15,7 @@ use game_logic::{
 mod assets;
 mod input;
 mod scenes;
+mod storage;
 
 const TOTAL_BOARD_H: usize = BOARD_H + MAX_WAVE_H + 1;
 
@@ -23,6 +24,19 @@ struct GameState {
     data_assets: assets::DataAssets,
     main_camera: ResourceId,
     env: game_logic::GameEnv,
+    difficulty: game_logic::difficulty::Difficulty,
+    profile: game_logic::profile::Profile,
+    touch: input::TouchState,
+    gamepad: input::GamepadState,
+}
+impl GameState {
+    fn save_profile(&self) {
+        storage::save(storage::PROFILE_KEY, &self.profile.to_yaml());
+    }
+    fn finish_run(&mut self) {
+        self.profile.record_run(&self.env.world);
+        self.save_profile();
+    }
 }
 
 impl Game for GameState {
@@ -30,6 +44,9 @@ impl Game for GameState {
         assets::load_assets(context);
         self.data_assets = assets::load_data_assets(context);
         self.main_camera = context.graphics.create_camera(1., Vector2f::ZERO);
+        if let Some(s) = storage::load(storage::PROFILE_KEY) {
+            self.profile = game_logic::profile::Profile::from_yaml(&s);
+        }
 
         context
             .graphics
@@ -53,8 +70,10 @@ impl Game for GameState {
         ) {
             return;
         };
-        if let Ok(vm) = game_logic::scripting::init_rune(&self.env.world) {
-            self.env.world.0.resources.vm = Some(vm);
+        // A script with errors keeps the previous Vm running
+        match game_logic::scripting::init_rune(&self.env.world) {
+            Ok(vm) => self.env.world.0.resources.vm = Some(vm),
+            Err(e) => log::error!("Scripts not reloaded: {}", e),
         }
     }
 }
@@ -35,7 +35,12 @@ impl Scene for Battle {
         context: &mut Context,
         scenes: &mut SceneController<Self::Game>,
     ) {
-        let input = crate::input::get_input_state(game.main_camera, context);
+        let input = crate::input::get_input_state(
+            game.main_camera,
+            &mut game.touch,
+            &mut game.gamepad,
+            context,
+        );
         if !game_graphics::battle::battle_draw(
             &mut self.graphics_state,
             &game.env.world,
@@ -25,12 +25,17 @@ impl Scene for Deck {
         context: &mut Context,
         scenes: &mut SceneController<Self::Game>,
     ) {
-        let input = crate::input::get_input_state(game.main_camera, context);
+        let input = crate::input::get_input_state(
+            game.main_camera,
+            &mut game.touch,
+            &mut game.gamepad,
+            context,
+        );
         game_graphics::deck::deck_draw(&mut self.graphics_state, &game.env.world, context, &input);
         let done = game_logic::deck::deck_update(&mut game.env);
 
         if done {
-            scenes.switch(Box::new(super::battle::Battle::default()));
+            scenes.switch(Box::new(super::map::Map::default()));
         }
     }
 }
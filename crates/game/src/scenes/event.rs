@@ -0,0 +1,49 @@
+use rogalik::prelude::*;
+
+use crate::GameState;
+
+#[derive(Default)]
+pub(crate) struct Event {
+    logic_state: game_logic::event::EventState,
+    graphics_state: game_graphics::event::EventGraphics,
+}
+impl Scene for Event {
+    type Game = GameState;
+
+    fn enter(
+        &mut self,
+        game: &mut Self::Game,
+        _context: &mut Context,
+        _scenes: &mut SceneController<Self::Game>,
+    ) {
+        game.env.input = Some(self.graphics_state.input_queue.subscribe());
+        game_logic::event::event_init(&mut self.logic_state, &mut game.env);
+    }
+
+    fn update(
+        &mut self,
+        game: &mut Self::Game,
+        context: &mut Context,
+        scenes: &mut SceneController<Self::Game>,
+    ) {
+        let input = crate::input::get_input_state(
+            game.main_camera,
+            &mut game.touch,
+            &mut game.gamepad,
+            context,
+        );
+        game_graphics::event::event_draw(
+            &self.logic_state,
+            &mut self.graphics_state,
+            &game.env.world,
+            context,
+            &input,
+        );
+        game_logic::event::event_update(&mut self.logic_state, &mut game.env);
+
+        if self.logic_state.done {
+            // Outcomes might have overfilled the deck
+            scenes.switch(Box::new(super::deck::Deck::default()));
+        }
+    }
+}
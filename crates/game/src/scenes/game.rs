@@ -9,6 +9,8 @@ impl GameScene {
     fn init_game(&mut self, game: &mut GameState, context: &mut Context) {
         game.env = game_logic::GameEnv::default();
         game.env.world = game_logic::World::default();
+        game.env.world.resources.difficulty = game.difficulty;
+        game.env.world.resources.unlocked_units = game.profile.unlocked_units.clone();
         load_data(
             &game.data_assets,
             &mut game.env.world.0.resources.data,
@@ -28,8 +30,14 @@ impl Scene for GameScene {
         scenes: &mut SceneController<Self::Game>,
     ) {
         match game.env.world.resources.game_mode {
-            GameMode::GameOver => scenes.switch(Box::new(super::game_over::GameOver)),
-            GameMode::Win => scenes.switch(Box::new(super::win::GameWin)),
+            GameMode::GameOver => {
+                game.finish_run();
+                scenes.switch(Box::new(super::game_over::GameOver));
+            }
+            GameMode::Win => {
+                game.finish_run();
+                scenes.switch(Box::new(super::win::GameWin));
+            }
             _ => (), // _ => scenes.push(Box::new(super::battle::Battle::default())),
         }
     }
@@ -42,7 +50,7 @@ impl Scene for GameScene {
         game.env.world.resources.game_mode = GameMode::Init;
         let _ = context.graphics.set_postprocess_strength("noise", 0.);
         self.init_game(game, context);
-        scenes.push(Box::new(super::battle::Battle::default()));
+        scenes.push(Box::new(super::village::Village::default()));
     }
     fn exit(
         &mut self,
@@ -19,7 +19,12 @@ impl Scene for GameOver {
         game_graphics::utils::draw_background(context);
         super::draw_centered_text("You have failed...", context);
 
-        let input = crate::input::get_input_state(game.main_camera, context);
+        let input = crate::input::get_input_state(
+            game.main_camera,
+            &mut game.touch,
+            &mut game.gamepad,
+            context,
+        );
         if input.click == ButtonState::Released {
             scenes.pop();
         }
@@ -1,6 +1,6 @@
 use rogalik::prelude::*;
 
-use game_graphics::input::ButtonState;
+use game_graphics::menu::MenuAction;
 
 use crate::GameState;
 
@@ -29,9 +29,17 @@ impl Scene for MainMenu {
             SpriteParams::default(),
         );
 
-        let input = crate::input::get_input_state(game.main_camera, context);
-        if input.click == ButtonState::Released {
-            scenes.push(Box::new(super::game::GameScene));
+        let input = crate::input::get_input_state(
+            game.main_camera,
+            &mut game.touch,
+            &mut game.gamepad,
+            context,
+        );
+        match game_graphics::menu::main_menu_draw(game.difficulty, &game.profile, context, &input) {
+            Some(MenuAction::Start) => scenes.push(Box::new(super::game::GameScene)),
+            Some(MenuAction::ChangeDifficulty(difficulty)) => game.difficulty = difficulty,
+            Some(MenuAction::Stats) => scenes.push(Box::new(super::stats::Stats)),
+            None => (),
         }
     }
 }
@@ -0,0 +1,50 @@
+use rogalik::prelude::*;
+
+use game_logic::map::NodeKind;
+
+use crate::GameState;
+
+#[derive(Default)]
+pub(crate) struct Map {
+    graphics_state: game_graphics::map::MapGraphics,
+}
+impl Scene for Map {
+    type Game = GameState;
+
+    fn enter(
+        &mut self,
+        game: &mut Self::Game,
+        _context: &mut Context,
+        _scenes: &mut SceneController<Self::Game>,
+    ) {
+        game.env.input = Some(self.graphics_state.input_queue.subscribe());
+    }
+
+    fn update(
+        &mut self,
+        game: &mut Self::Game,
+        context: &mut Context,
+        scenes: &mut SceneController<Self::Game>,
+    ) {
+        let input = crate::input::get_input_state(
+            game.main_camera,
+            &mut game.touch,
+            &mut game.gamepad,
+            context,
+        );
+        game_graphics::map::map_draw(&mut self.graphics_state, &game.env.world, context, &input);
+
+        match game_logic::map::map_update(&mut game.env) {
+            Some(NodeKind::Battle) | Some(NodeKind::Elite) => {
+                scenes.switch(Box::new(super::battle::Battle::default()));
+            }
+            Some(NodeKind::Shop) => {
+                scenes.switch(Box::new(super::shop::Shop::default()));
+            }
+            Some(NodeKind::Event) => {
+                scenes.switch(Box::new(super::event::Event::default()));
+            }
+            _ => (),
+        }
+    }
+}
@@ -2,10 +2,14 @@ use rogalik::prelude::*;
 
 mod battle;
 mod deck;
+mod event;
 mod game;
 mod game_over;
 mod main_menu;
+mod map;
 mod shop;
+mod stats;
+mod village;
 mod win;
 
 pub(crate) use main_menu::MainMenu;
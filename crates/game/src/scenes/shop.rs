@@ -34,7 +34,12 @@ impl Scene for Shop {
         context: &mut Context,
         scenes: &mut SceneController<Self::Game>,
     ) {
-        let input = crate::input::get_input_state(game.main_camera, context);
+        let input = crate::input::get_input_state(
+            game.main_camera,
+            &mut game.touch,
+            &mut game.gamepad,
+            context,
+        );
         game_graphics::shop::shop_draw(
             &self.logic_state,
             &mut self.graphics_state,
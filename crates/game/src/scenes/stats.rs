@@ -0,0 +1,27 @@
+use rogalik::prelude::*;
+
+use crate::GameState;
+
+pub(crate) struct Stats;
+impl Scene for Stats {
+    type Game = GameState;
+
+    fn update(
+        &mut self,
+        game: &mut Self::Game,
+        context: &mut Context,
+        scenes: &mut SceneController<Self::Game>,
+    ) {
+        game_graphics::utils::draw_background(context);
+
+        let input = crate::input::get_input_state(
+            game.main_camera,
+            &mut game.touch,
+            &mut game.gamepad,
+            context,
+        );
+        if game_graphics::menu::stats_draw(&game.profile, context, &input) {
+            scenes.pop();
+        }
+    }
+}
@@ -0,0 +1,42 @@
+//! Local persistence: a file next to the executable on desktop
+//! and the browser's local storage on the web.
+
+pub const PROFILE_KEY: &str = "grimvaders_profile";
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load(key: &str) -> Option<String> {
+    std::fs::read_to_string(get_path(key)).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save(key: &str, value: &str) {
+    if let Err(e) = std::fs::write(get_path(key), value) {
+        log::error!("Can't save {}: {}", key, e);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn get_path(key: &str) -> std::path::PathBuf {
+    let dir = std::env::current_exe()
+        .ok()
+        .and_then(|p| p.parent().map(|p| p.to_path_buf()))
+        .unwrap_or_default();
+    dir.join(format!("{}.yaml", key))
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn load(key: &str) -> Option<String> {
+    let storage = web_sys::window()?.local_storage().ok()??;
+    storage.get_item(key).ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn save(key: &str, value: &str) {
+    let Some(storage) = web_sys::window().and_then(|w| w.local_storage().ok().flatten()) else {
+        log::error!("Local storage is not available!");
+        return;
+    };
+    if storage.set_item(key, value).is_err() {
+        log::error!("Can't save {}!", key);
+    }
+}
@@ -0,0 +1,113 @@
+use serde::Deserialize;
+
+use crate::GameData;
+
+/// Simple unit ability, executed by the engine without a script.
+#[derive(Clone, Deserialize)]
+pub struct AbilityData {
+    // Trigger kind, eg. on_fight
+    pub trigger: String,
+    pub target: Target,
+    pub effect: Effect,
+    pub amount: i32,
+    // Only trigger when the host stands on the given tile
+    #[serde(default)]
+    pub tile: Option<String>,
+}
+impl AbilityData {
+    pub fn describe(&self, data: &GameData) -> String {
+        let mut output = format!(
+            "{}: {}",
+            describe_trigger(&self.trigger),
+            self.describe_effect(data)
+        );
+        if let Some(tile) = &self.tile {
+            output += &format!(", if on a {} tile", tile.to_lowercase());
+        }
+        output + "."
+    }
+    fn describe_effect(&self, data: &GameData) -> String {
+        let amount = format!("{:+}", self.amount);
+        match self.effect {
+            Effect::Food if self.amount < 0 => format!("steal {} food", -self.amount),
+            Effect::Food => format!("gain {} food", amount),
+            Effect::TownHealth if self.amount < 0 => {
+                format!("damage the town by {}", -self.amount)
+            }
+            Effect::TownHealth => format!("repair the town by {}", self.amount),
+            Effect::Health => {
+                let target = match &self.target {
+                    Target::Host if self.amount < 0 => {
+                        return format!("take {} damage", -self.amount)
+                    }
+                    Target::Host => return format!("gain {} health", amount),
+                    Target::AllPlayers => "all units".to_string(),
+                    Target::AllPlayersWithTag(tag) => {
+                        format!("all {} units", data.tag_name(tag).to_lowercase())
+                    }
+                    Target::InFront => "the unit in front".to_string(),
+                    Target::Adjacent => "adjacent units".to_string(),
+                    Target::Column => "all units in the column".to_string(),
+                };
+                if self.amount < 0 {
+                    format!("deal {} damage to {}", -self.amount, target)
+                } else {
+                    format!("give {} health to {}", amount, target)
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Target {
+    Host,
+    AllPlayers,
+    AllPlayersWithTag(String),
+    // Player units relative to the host
+    InFront,
+    Adjacent,
+    Column,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Effect {
+    Health,
+    Food,
+    TownHealth,
+}
+
+/// Unique name of the ability, used in place of a script function name.
+pub fn ability_key(entity: &str, idx: usize) -> String {
+    format!("{}#{}", entity, idx)
+}
+
+fn describe_trigger(trigger: &str) -> String {
+    match trigger {
+        "on_spawn" => "On spawn",
+        "on_fight" => "On fight start",
+        "on_kill" => "On kill",
+        "on_attack" => "On attack",
+        "on_damage" => "On non lethal damage",
+        "on_wave_start" => "On wave start",
+        "on_wave_end" => "On wave end",
+        "on_battle_end" => "On battle end",
+        "on_move" => "When moved",
+        "on_town_hit" => "When the town is hit",
+        "on_enemy_spawn" => "When an enemy appears",
+        "on_enemy_kill" => "When an enemy is killed",
+        "on_enemy_heal" => "When an enemy gains health",
+        "on_enemy_damage" => "When an enemy receives damage",
+        "on_before_damage" => "Before receiving damage",
+        "on_before_heal" => "Before gaining health",
+        "on_before_food" => "Before food is yielded",
+        "on_ally_kill" => "When an ally is killed",
+        "on_ally_heal" => "When an ally gains health",
+        "on_ally_damage" => "When an ally receives non lethal damage",
+        "on_ally_gain_food" => "When an ally yields food",
+        a => a,
+    }
+    .to_string()
+}
@@ -1,21 +1,67 @@
 use serde::Deserialize;
 use std::collections::HashMap;
 
+pub mod abilities;
 pub mod sprites;
 
+pub use abilities::{ability_key, AbilityData};
+
 #[derive(Clone, Default)]
 pub struct GameData {
     pub entities: HashMap<String, EntityData>,
     pub categories: HashMap<String, Vec<String>>,
+    pub events: HashMap<String, EventData>,
+    pub villages: HashMap<String, VillageData>,
+    // Keyed by `ability_key`
+    pub abilities: HashMap<String, AbilityData>,
+    pub tags: HashMap<String, TagData>,
+    pub synergies: HashMap<String, SynergyData>,
+    // Rune sources from the .rn files, keyed by the file name
+    pub scripts: HashMap<String, String>,
 }
 impl GameData {
     pub fn add_entities(&mut self, s: &str, category: &str) {
         let map: HashMap<String, EntityData> =
             serde_yaml::from_str(s).expect("Can't parse yaml data!");
         let inserted = map.keys().map(|s| s.to_string()).collect();
+        for (name, data) in map.iter() {
+            for (i, ability) in data.abilities.iter().enumerate() {
+                self.abilities.insert(ability_key(name, i), ability.clone());
+            }
+        }
         self.entities.extend(map);
         self.categories.insert(category.to_string(), inserted);
     }
+    pub fn add_events(&mut self, s: &str) {
+        let map: HashMap<String, EventData> =
+            serde_yaml::from_str(s).expect("Can't parse yaml data!");
+        self.events.extend(map);
+    }
+    pub fn add_tags(&mut self, s: &str) {
+        let map: HashMap<String, TagData> =
+            serde_yaml::from_str(s).expect("Can't parse yaml data!");
+        self.tags.extend(map);
+    }
+    /// Display name of a tag, falls back to the tag itself.
+    pub fn tag_name<'a>(&'a self, tag: &'a str) -> &'a str {
+        self.tags.get(tag).map(|t| t.name.as_str()).unwrap_or(tag)
+    }
+    pub fn has_tag_rule(&self, tag: &str, rule: TagRule) -> bool {
+        self.tags.get(tag).is_some_and(|t| t.rules.contains(&rule))
+    }
+    pub fn add_synergies(&mut self, s: &str) {
+        let map: HashMap<String, SynergyData> =
+            serde_yaml::from_str(s).expect("Can't parse yaml data!");
+        self.synergies.extend(map);
+    }
+    pub fn add_script(&mut self, name: &str, s: &str) {
+        self.scripts.insert(name.to_string(), s.to_string());
+    }
+    pub fn add_villages(&mut self, s: &str) {
+        let map: HashMap<String, VillageData> =
+            serde_yaml::from_str(s).expect("Can't parse yaml data!");
+        self.villages.extend(map);
+    }
 }
 
 #[derive(Clone, Deserialize)]
@@ -25,11 +71,35 @@ pub struct EntityData {
     pub chance: Option<f32>,
     pub tier: Option<u32>,
     pub score: Option<u32>,
+    // Best level that has to be reached in any run, to make the unit available
+    pub unlock: Option<u32>,
     #[serde(default)]
     pub script: Option<String>,
+    // Name of a file in the scripts directory (without the extension)
+    #[serde(default)]
+    pub script_file: Option<String>,
+    #[serde(default)]
+    pub abilities: Vec<AbilityData>,
     #[serde(default)]
     pub description: Option<String>,
 }
+impl EntityData {
+    /// Generated ability descriptions, followed by the hand-written one.
+    pub fn get_description(&self, data: &GameData) -> Option<String> {
+        let mut lines = self
+            .abilities
+            .iter()
+            .map(|a| a.describe(data))
+            .collect::<Vec<_>>();
+        if let Some(description) = &self.description {
+            lines.push(description.trim().to_string());
+        }
+        if lines.is_empty() {
+            return None;
+        }
+        Some(lines.join("\n") + "\n")
+    }
+}
 
 #[derive(Clone, Deserialize, Default)]
 pub struct SpriteData {
@@ -39,3 +109,88 @@ pub struct SpriteData {
     pub frames: Option<usize>,
     pub tag: Option<String>,
 }
+
+#[derive(Clone, Deserialize)]
+pub struct EventData {
+    pub text: String,
+    pub options: Vec<EventOption>,
+    pub chance: Option<f32>,
+    #[serde(default)]
+    pub script: Option<String>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct EventOption {
+    pub text: String,
+    // Rune function name, no outcome if empty
+    #[serde(default)]
+    pub outcome: Option<String>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct VillageData {
+    pub units: Vec<SquadSlot>,
+    pub health: u32,
+    // Rune function name, run on every fight start
+    #[serde(default)]
+    pub passive: Option<String>,
+    #[serde(default)]
+    pub script: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(untagged)]
+pub enum SquadSlot {
+    Unit(String),
+    // One of the units is picked at random
+    Random(Vec<String>),
+}
+impl From<&SquadSlot> for String {
+    fn from(value: &SquadSlot) -> Self {
+        match value {
+            SquadSlot::Unit(name) => name.to_string(),
+            SquadSlot::Random(names) => names.join("/"),
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+pub struct TagData {
+    pub name: String,
+    // Index in the small icons atlas
+    #[serde(default)]
+    pub icon: Option<usize>,
+    #[serde(default)]
+    pub rules: Vec<TagRule>,
+}
+
+/// Engine behaviours that can be attached to a tag.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TagRule {
+    // Can not be moved once placed
+    Immovable,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct SynergyData {
+    pub tag: String,
+    // Number of units with the tag required on the board
+    pub count: u32,
+    pub effect: SynergyEffect,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SynergyEffect {
+    // Added to every heal received by a player unit
+    HealBonus(u32),
+    // Player units sharing a column with a tagged unit can not be moved
+    ColumnImmovable,
+    // Yielded on fight start
+    Food(u32),
+}
@@ -4,6 +4,7 @@ use wunderkammer::prelude::*;
 
 mod board;
 mod player;
+mod trace;
 mod utils;
 
 use crate::{
@@ -12,14 +13,18 @@ use crate::{
         sprites::{
             animate_unit_sprite, attack_town, attack_unit_sprite, get_unit_sprite,
             kill_unit_sprite, move_unit_sprite, place_unit_sprite, purge_unit_sprites,
-            remove_unit_sprite, UnitSprite,
+            remove_unit_sprite, reset_unit_sprites, UnitSprite,
         },
     },
-    globals::{BASE_TEXT_SIZE, FOOD_COLOR, FOOD_ICON, GAP, HEALTH_ICON, RED_COLOR, SPRITE_SIZE},
+    globals::{
+        BASE_TEXT_SIZE, FOOD_COLOR, FOOD_ICON, GAP, GHOST_ALPHA, HEALTH_ICON, ICON_SIZE, OVERLAY_Z,
+        RED_COLOR, SPRITE_SIZE, TILE_SIZE,
+    },
     input::InputState,
-    utils::get_viewport_bounds,
+    ui::Span,
+    utils::{get_viewport_bounds, tile_to_world},
 };
-use game_logic::{commands, GameEnv, InputEvent, World};
+use game_logic::{commands, components::Position, GameEnv, InputEvent, World};
 
 #[derive(Default, Eq, PartialEq)]
 enum InputMode {
@@ -32,6 +37,13 @@ enum InputMode {
 #[derive(Default)]
 pub struct BattleGraphics {
     input_mode: InputMode,
+    // Keyboard controlled board cursor
+    cursor: Option<Position>,
+    // Unit being dragged from the hand or the board
+    drag: Option<Entity>,
+    inspect: Option<Entity>,
+    // Command trace overlay, debug builds only
+    show_trace: bool,
     pub input_queue: ObservableQueue<InputEvent>,
     observers: Vec<Box<dyn ErasedObserver>>,
     unit_sprites: Vec<UnitSprite>,
@@ -68,10 +80,22 @@ pub fn battle_draw(
         is_animating |= animate_unit_sprite(sprite, context.time.get_delta());
     }
     purge_unit_sprites(&mut state.unit_sprites);
+    if !is_animating {
+        draw_preview(state, world, context);
+    }
 
     is_animating |= crate::draw::bubbles::update_bubbles(&mut state.bubbles, context);
     player::handle_player_ui(world, state, context, input_state, !is_animating);
 
+    if cfg!(debug_assertions) {
+        if input_state.debug {
+            state.show_trace = !state.show_trace;
+        }
+        if state.show_trace {
+            trace::draw_trace(world, context);
+        }
+    }
+
     // TEMP?
     while let Some(sound) = state.sounds.pop_front() {
         let _ = context.audio.play(sound, false);
@@ -80,6 +104,61 @@ pub fn battle_draw(
     is_animating
 }
 
+fn draw_preview(state: &BattleGraphics, world: &World, context: &mut Context) {
+    let Some(preview) = &world.resources.battle_state.preview else {
+        return;
+    };
+
+    // Ghosts of the units expected to die
+    for entity in preview.deaths.iter() {
+        let Some(sprite) = get_unit_sprite(*entity, &state.unit_sprites) else {
+            continue;
+        };
+        let _ = context.graphics.draw_atlas_sprite(
+            "disintegrate",
+            sprite.index,
+            sprite.origin,
+            OVERLAY_Z,
+            Vector2f::splat(SPRITE_SIZE),
+            SpriteParams {
+                color: Color(255, 255, 255, GHOST_ALPHA),
+                ..Default::default()
+            },
+        );
+    }
+
+    // Expected town changes, over the town tiles
+    let mut spans = Vec::new();
+    if preview.town_health != 0 {
+        spans.push(preview_span(HEALTH_ICON, preview.town_health, RED_COLOR));
+    }
+    if preview.food != 0 {
+        spans.push(preview_span(FOOD_ICON, preview.food, FOOD_COLOR));
+    }
+
+    let mut origin = tile_to_world(Position::new(0, -1)) + Vector2f::new(0., 0.5 * TILE_SIZE);
+    for span in spans {
+        span.draw(origin, OVERLAY_Z, context);
+        origin.x += span.width(context);
+    }
+}
+
+fn preview_span<'a>(icon: usize, value: i32, color: Color) -> Span<'a> {
+    Span::new()
+        .with_sprite("icons_small", icon)
+        .with_spacer(2.)
+        .with_text_owned(format!("{:+}", value))
+        .with_spacer(4.)
+        .with_sprite_size(ICON_SIZE)
+        .with_text_size(BASE_TEXT_SIZE)
+        .with_text_color(ghost_color(color))
+        .with_sprite_color(ghost_color(Color(255, 255, 255, 255)))
+}
+
+fn ghost_color(color: Color) -> Color {
+    Color(color.0, color.1, color.2, GHOST_ALPHA)
+}
+
 fn handle_events(state: &mut BattleGraphics, world: &World) {
     let mut observers = std::mem::take(&mut state.observers);
     for observer in observers.iter_mut() {
@@ -169,6 +248,23 @@ fn subscribe_events(env: &mut GameEnv, state: &mut BattleGraphics) {
             ));
         },
     )));
+    observers.push(Box::new(CommandObserver::new(
+        &mut env.scheduler,
+        |c: &commands::ChangeTownHealth, _, s| {
+            if c.0 == 0 {
+                return;
+            }
+            if c.0 > 0 {
+                s.sounds.push_back("heal");
+            }
+            s.bubbles.push(Bubble::new(
+                s.status_origin + Vector2f::splat(2. * BASE_TEXT_SIZE),
+                RED_COLOR,
+                Some(format!("{:+}", c.0)),
+                Some(HEALTH_ICON),
+            ));
+        },
+    )));
     observers.push(Box::new(CommandObserver::new(
         &mut env.scheduler,
         |c: &commands::Kill, _, s| kill_unit_sprite(c.0, &mut s.unit_sprites),
@@ -177,6 +273,15 @@ fn subscribe_events(env: &mut GameEnv, state: &mut BattleGraphics) {
         &mut env.scheduler,
         |c: &commands::RemoveUnit, _, s| remove_unit_sprite(c.0, &mut s.unit_sprites),
     )));
+    observers.push(Box::new(CommandObserver::new(
+        &mut env.scheduler,
+        // The new unit's sprite is placed by the following SpawnUnit
+        |c: &commands::Transform, _, s| remove_unit_sprite(c.0, &mut s.unit_sprites),
+    )));
+    observers.push(Box::new(CommandObserver::new(
+        &mut env.scheduler,
+        |_: &commands::Undo, w, s| reset_unit_sprites(w, &mut s.unit_sprites),
+    )));
 
     state.observers = observers;
 }
@@ -1,17 +1,29 @@
 use rogalik::prelude::*;
+use wunderkammer::prelude::*;
 
-use game_logic::{get_unit_at, is_on_board, is_on_extended_board, InputEvent, World};
+use game_logic::{
+    battle::BattleMode,
+    can_place_unit,
+    components::Position,
+    get_unit_at,
+    globals::{BOARD_H, BOARD_W},
+    is_on_board, is_on_extended_board, InputEvent, World,
+};
 
 use crate::{
-    draw::units::draw_deck_button,
+    draw::{
+        sprites::get_sprite_data,
+        units::{draw_deck_button, draw_inspect_panel},
+    },
     globals::{
         ACTION_BUTTON_W, BASE_TEXT_SIZE, BUTTON_SIZE, BUTTON_TEXT_COLOR, CURSOR_SPIRTE,
-        DECK_BUTTON_H, DECK_BUTTON_W, FIGHT_ICON, FOOD_COLOR, FOOD_ICON, GAP, HEALTH_ICON,
-        ICON_SIZE, OVERLAY_Z, PANEL_SPRTE, RED_COLOR, SPRITE_SIZE, TILE_SIZE, TOWN_ICON, UI_Z,
+        DECK_BUTTON_H, DECK_BUTTON_W, FIGHT_ICON, FOOD_COLOR, FOOD_ICON, GAP, GHOST_ALPHA,
+        HEALTH_ICON, ICON_SIZE, OVERLAY_Z, PANEL_SPRTE, RED_COLOR, SPRITE_SIZE, TILE_SIZE,
+        TOWN_ICON, UI_Z,
     },
     input::{ButtonState, InputState},
-    ui::{Button, Span},
-    utils::{get_viewport_bounds, tile_to_world, world_to_tile},
+    ui::{Button, Span, TextBox},
+    utils::{get_hit_margin, get_viewport_bounds, is_mouse_over, tile_to_world, world_to_tile},
 };
 
 use super::InputMode;
@@ -24,6 +36,23 @@ pub(super) fn handle_player_ui(
     take_input: bool,
 ) {
     draw_status(state, world, context);
+    draw_modifiers(state, world, context);
+    draw_synergies(world, context, input_state);
+
+    if let Some(entity) = state.inspect {
+        draw_inspect_panel(entity, world, context);
+        if input_state.click == ButtonState::Released || input_state.inspect {
+            state.inspect = None;
+        }
+        handle_hand(state, world, context, input_state, false);
+        handle_input_player(state, world, context, input_state, false);
+        return;
+    }
+
+    let take_input = take_input
+        && !handle_inspect(state, world, context, input_state)
+        && !handle_drag(state, world, context, input_state);
+
     handle_hand(state, world, context, input_state, take_input);
     handle_input_player(state, world, context, input_state, take_input);
     if take_input {
@@ -57,13 +86,49 @@ fn handle_input_player(
         return;
     }
 
-    if fight.clicked(input_state) {
+    if fight.clicked(input_state) || input_state.fight {
         state.input_queue.push(InputEvent::Done);
     }
 
-    if input_state.click == ButtonState::Released {
-        let tile = world_to_tile(input_state.mouse_world_position);
+    if world.resources.battle_state.can_undo() {
+        let undo = Button::new(
+            Vector2f::new(
+                bounds.1.x - ACTION_BUTTON_W - GAP,
+                bounds.0.y + BUTTON_SIZE + 2. * GAP,
+            ),
+            Vector2f::new(ACTION_BUTTON_W, BUTTON_SIZE),
+            UI_Z,
+        )
+        .with_span(Span::new().with_text_borrowed("Undo [Z]"));
+        undo.draw(context, input_state);
+
+        if undo.clicked(input_state) || input_state.undo {
+            state.input_queue.push(InputEvent::Undo);
+            state.input_mode = InputMode::None;
+            return;
+        }
+    }
+
+    if let Some(dir) = input_state.dir {
+        state.cursor = match state.cursor {
+            // First key press only shows the cursor
+            None => Some(Position::new(0, 0)),
+            Some(p) => Some(Position::new(p.x + dir.x, p.y + dir.y))
+                .filter(|t| is_on_board(*t))
+                .or(Some(p)),
+        };
+    }
+
+    let target = if input_state.click == ButtonState::Released {
+        state.cursor = None;
+        Some(world_to_tile(input_state.mouse_world_position))
+    } else if input_state.action {
+        state.cursor
+    } else {
+        None
+    };
 
+    if let Some(tile) = target {
         if is_on_board(tile) {
             if let Some(entity) = get_unit_at(world, tile) {
                 state.input_mode = InputMode::BoardUnit(entity);
@@ -105,7 +170,9 @@ fn draw_cursor(
         }
     }
 
-    let tile = world_to_tile(input_state.mouse_world_position);
+    let tile = state
+        .cursor
+        .unwrap_or(world_to_tile(input_state.mouse_world_position));
 
     if !is_on_extended_board(tile) {
         return;
@@ -121,30 +188,165 @@ fn draw_cursor(
     );
 }
 
-fn handle_hand(
+fn handle_inspect(
+    state: &mut super::BattleGraphics,
+    world: &World,
+    context: &Context,
+    input_state: &InputState,
+) -> bool {
+    if !input_state.inspect {
+        return false;
+    }
+    let entity = get_hovered_hand_unit(world, context, input_state).or_else(|| {
+        state
+            .unit_sprites
+            .iter()
+            .find(|s| s.mouse_over(input_state))
+            .map(|s| s.entity)
+    });
+    if entity.is_some() {
+        state.inspect = entity;
+        state.drag = None;
+        return true;
+    }
+    false
+}
+
+/// Returns true if a unit has been dropped.
+fn handle_drag(
     state: &mut super::BattleGraphics,
     world: &World,
     context: &mut Context,
     input_state: &InputState,
-    take_input: bool,
-) {
+) -> bool {
+    match input_state.click {
+        ButtonState::Pressed => {
+            state.drag = get_hovered_hand_unit(world, context, input_state).or_else(|| {
+                get_unit_at(world, world_to_tile(input_state.mouse_world_position))
+                    .filter(|e| world.components.player.get(*e).is_some())
+            });
+            false
+        }
+        ButtonState::Down => {
+            if let Some(entity) = state.drag {
+                draw_drag(entity, world, context, input_state);
+            }
+            false
+        }
+        ButtonState::Released => {
+            let Some(entity) = state.drag.take() else {
+                return false;
+            };
+            let tile = world_to_tile(input_state.mouse_world_position);
+            // Releasing over the source falls back to click selection
+            if !can_place_unit(world, entity, tile) {
+                return false;
+            }
+            if world.resources.player_data.deck.contains(&entity) {
+                state
+                    .input_queue
+                    .push(InputEvent::SummonPlayer(entity, tile));
+            } else {
+                state.input_queue.push(InputEvent::MoveUnit(entity, tile));
+            }
+            state.input_mode = InputMode::None;
+            true
+        }
+        ButtonState::Up => false,
+    }
+}
+
+fn draw_drag(entity: Entity, world: &World, context: &mut Context, input_state: &InputState) {
+    // Highlight valid targets
+    for y in 0..BOARD_H {
+        for x in 0..BOARD_W {
+            let tile = Position::new(x as i32, y as i32);
+            if !can_place_unit(world, entity, tile) {
+                continue;
+            }
+            let _ = context.graphics.draw_atlas_sprite(
+                "tiles",
+                CURSOR_SPIRTE,
+                tile_to_world(tile),
+                OVERLAY_Z,
+                Vector2f::splat(TILE_SIZE),
+                SpriteParams {
+                    color: Color(FOOD_COLOR.0, FOOD_COLOR.1, FOOD_COLOR.2, GHOST_ALPHA),
+                    ..Default::default()
+                },
+            );
+        }
+    }
+
+    let Some(sprite) = world
+        .components
+        .name
+        .get(entity)
+        .and_then(|n| get_sprite_data(n, world))
+    else {
+        return;
+    };
+    let _ = context.graphics.draw_atlas_sprite(
+        &sprite.atlas,
+        sprite.index,
+        input_state.mouse_world_position - Vector2f::splat(0.5 * SPRITE_SIZE),
+        UI_Z,
+        Vector2f::splat(SPRITE_SIZE),
+        SpriteParams {
+            color: Color(255, 255, 255, GHOST_ALPHA),
+            ..Default::default()
+        },
+    );
+}
+
+fn get_hand_origin(i: usize, context: &Context) -> Vector2f {
     let bounds = get_viewport_bounds(context);
     let bottom = bounds.0.y + BUTTON_SIZE + 2. * GAP;
     let base = Vector2f::new(bounds.1.x - SPRITE_SIZE - GAP, bottom + BUTTON_SIZE + GAP);
-
     let step = game_logic::globals::DECK_SIZE / 2;
+    base + Vector2f::new(
+        -(GAP + DECK_BUTTON_W) * (i % step) as f32,
+        (GAP + DECK_BUTTON_H) * (i / step) as f32,
+    )
+}
 
+fn get_hovered_hand_unit(
+    world: &World,
+    context: &Context,
+    input_state: &InputState,
+) -> Option<Entity> {
+    world
+        .0
+        .resources
+        .player_data
+        .deck
+        .iter()
+        .enumerate()
+        .find(|(i, _)| {
+            let margin = get_hit_margin(context);
+            is_mouse_over(
+                get_hand_origin(*i, context) - Vector2f::splat(margin),
+                Vector2f::new(DECK_BUTTON_W, DECK_BUTTON_H) + Vector2f::splat(2. * margin),
+                input_state,
+            )
+        })
+        .map(|(_, e)| *e)
+}
+
+fn handle_hand(
+    state: &mut super::BattleGraphics,
+    world: &World,
+    context: &mut Context,
+    input_state: &InputState,
+    take_input: bool,
+) {
     for (i, &entity) in world.0.resources.player_data.deck.iter().enumerate() {
-        let origin = base
-            + Vector2f::new(
-                -(GAP + DECK_BUTTON_W) * (i % step) as f32,
-                (GAP + DECK_BUTTON_H) * (i / step) as f32,
-            );
+        let origin = get_hand_origin(i, context);
         let selected = state.input_mode == InputMode::HandUnit(entity);
 
         let clicked = draw_deck_button(entity, origin, UI_Z, selected, world, context, input_state);
 
-        if take_input && clicked {
+        if take_input && (clicked || input_state.digit == Some(i)) {
             if selected {
                 state.input_mode = InputMode::None
             } else {
@@ -177,7 +379,10 @@ pub(super) fn draw_status(state: &super::BattleGraphics, world: &World, context:
         Span::new()
             .with_sprite("icons_small", HEALTH_ICON)
             .with_spacer(2.)
-            .with_text_owned(format!("{}", world.resources.player_data.health))
+            .with_text_owned(format!(
+                "{}/{}",
+                world.resources.player_data.health, world.resources.player_data.max_health
+            ))
             .with_spacer(4.)
             .with_sprite_size(ICON_SIZE)
             .with_text_size(BASE_TEXT_SIZE)
@@ -239,3 +444,115 @@ pub(super) fn draw_status(state: &super::BattleGraphics, world: &World, context:
         origin.x += span.width(context);
     }
 }
+
+fn draw_modifiers(state: &super::BattleGraphics, world: &World, context: &mut Context) {
+    // Above the status panel
+    let mut origin = state.status_origin + Vector2f::new(0., BASE_TEXT_SIZE + 8. + GAP);
+    for modifier in world.resources.difficulty.modifiers().iter() {
+        let _ = context.graphics.draw_text(
+            "default",
+            &String::from(modifier),
+            origin,
+            UI_Z,
+            BASE_TEXT_SIZE,
+            SpriteParams {
+                color: RED_COLOR,
+                ..Default::default()
+            },
+        );
+        origin.y += BASE_TEXT_SIZE + GAP;
+    }
+}
+
+fn draw_synergies(world: &World, context: &mut Context, input_state: &InputState) {
+    let data = &world.resources.data;
+    let mut names = data.synergies.keys().collect::<Vec<_>>();
+    if names.is_empty() {
+        return;
+    }
+    names.sort();
+
+    // During the fight only the synergies activated on fight start apply
+    let active = match world.resources.battle_state.mode {
+        BattleMode::Plan => game_logic::battle::get_reached_synergies(world),
+        _ => world.resources.battle_state.synergies.clone(),
+    };
+
+    let spans = names
+        .iter()
+        .filter_map(|name| {
+            let synergy = data.synergies.get(*name)?;
+            let color = if active.contains(*name) {
+                FOOD_COLOR
+            } else {
+                BUTTON_TEXT_COLOR
+            };
+            let mut span = Span::new();
+            if let Some(icon) = data.tags.get(&synergy.tag).and_then(|t| t.icon) {
+                span = span
+                    .with_sprite("icons_small", icon)
+                    .with_spacer(2.)
+                    .with_sprite_size(ICON_SIZE);
+            }
+            Some(
+                span.with_text_owned(format!(
+                    "{} {}/{}",
+                    name,
+                    game_logic::battle::get_tag_count(world, &synergy.tag),
+                    synergy.count
+                ))
+                .with_text_size(BASE_TEXT_SIZE)
+                .with_text_color(color),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let ov = 4.;
+    let oh = 8.;
+    let line_h = BASE_TEXT_SIZE + GAP;
+    let w = spans.iter().map(|s| s.width(context)).fold(0., f32::max);
+    let size = Vector2f::new(w + 2. * oh, spans.len() as f32 * line_h - GAP + 2. * ov);
+
+    let bounds = get_viewport_bounds(context);
+    let origin = bounds.1 - size - Vector2f::splat(GAP);
+
+    let _ = context.graphics.draw_atlas_sprite(
+        "ui",
+        PANEL_SPRTE,
+        origin,
+        UI_Z,
+        size,
+        SpriteParams {
+            slice: Some((4, Vector2f::splat(SPRITE_SIZE))),
+            ..Default::default()
+        },
+    );
+
+    let mut line_origin = origin + Vector2f::new(oh, size.y - ov - BASE_TEXT_SIZE);
+    for (name, span) in names.iter().zip(spans) {
+        span.draw(line_origin, UI_Z, context);
+        if is_mouse_over(line_origin, Vector2f::new(w, BASE_TEXT_SIZE), input_state) {
+            draw_synergy_description(name, origin.y - GAP, world, context);
+        }
+        line_origin.y -= line_h;
+    }
+}
+
+fn draw_synergy_description(name: &str, top: f32, world: &World, context: &mut Context) {
+    let Some(synergy) = world.resources.data.synergies.get(name) else {
+        return;
+    };
+    let content = format!(
+        "{} x{}: {}",
+        world.resources.data.tag_name(&synergy.tag),
+        synergy.count,
+        synergy.description.as_deref().unwrap_or_default().trim()
+    );
+    let bounds = get_viewport_bounds(context);
+    TextBox::owned(content).draw(
+        Vector2f::new(bounds.0.x + GAP, top - BASE_TEXT_SIZE),
+        bounds.1.x - bounds.0.x - 2. * GAP,
+        UI_Z,
+        context,
+    );
+}
@@ -0,0 +1,73 @@
+use rogalik::prelude::*;
+
+use game_logic::{trace::TraceKind, World};
+
+use crate::{
+    globals::{
+        BASE_TEXT_SIZE, BUTTON_TEXT_COLOR, FOOD_COLOR, GAP, PANEL_SPRTE, RED_COLOR, SPRITE_SIZE,
+        UI_Z,
+    },
+    utils::get_viewport_bounds,
+};
+
+const MAX_TRACE_LINES: usize = 24;
+const TRACE_INDENT: f32 = 8.;
+
+/// Lists the most recent commands, children indented under their parents.
+pub(super) fn draw_trace(world: &World, context: &mut Context) {
+    let trace = &world.resources.trace;
+    let skip = trace.entries.len().saturating_sub(MAX_TRACE_LINES);
+
+    let lines = trace
+        .entries
+        .iter()
+        .skip(skip)
+        .map(|e| {
+            let (text, color) = match (e.kind, e.rejected_by) {
+                (_, Some(handler)) => (format!("{} x {}", e.label, handler), RED_COLOR),
+                (TraceKind::Script, _) => (e.label.clone(), FOOD_COLOR),
+                (TraceKind::Command, _) => match e.sent_by {
+                    Some(handler) => (format!("{} < {}", e.label, handler), BUTTON_TEXT_COLOR),
+                    None => (e.label.clone(), BUTTON_TEXT_COLOR),
+                },
+            };
+            (trace.depth(e) as f32 * TRACE_INDENT, text, color)
+        })
+        .collect::<Vec<_>>();
+
+    let bounds = get_viewport_bounds(context);
+    let size = Vector2f::new(
+        bounds.1.x - bounds.0.x - 2. * GAP,
+        lines.len() as f32 * BASE_TEXT_SIZE + 2. * GAP,
+    );
+    let origin = Vector2f::new(bounds.0.x + GAP, bounds.1.y - GAP - size.y);
+
+    let _ = context.graphics.draw_atlas_sprite(
+        "ui",
+        PANEL_SPRTE,
+        origin,
+        UI_Z + 10,
+        size,
+        SpriteParams {
+            slice: Some((4, Vector2f::splat(SPRITE_SIZE))),
+            color: Color(255, 255, 255, 224),
+            ..Default::default()
+        },
+    );
+
+    let mut line_origin = origin + Vector2f::new(GAP, size.y - GAP - BASE_TEXT_SIZE);
+    for (indent, text, color) in lines {
+        let _ = context.graphics.draw_text(
+            "default",
+            &text,
+            line_origin + Vector2f::new(indent, 0.),
+            UI_Z + 11,
+            BASE_TEXT_SIZE,
+            SpriteParams {
+                color,
+                ..Default::default()
+            },
+        );
+        line_origin.y -= BASE_TEXT_SIZE;
+    }
+}
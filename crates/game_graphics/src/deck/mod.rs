@@ -27,6 +27,18 @@ pub fn deck_draw(
 ) {
     crate::utils::draw_background(context);
 
+    if let Some(dir) = input_state.dir {
+        let deck = &world.0.resources.player_data.deck;
+        let current = state
+            .selected
+            .and_then(|e| deck.iter().position(|a| *a == e));
+        let next = match current {
+            Some(i) => (i as i32 + dir.x).rem_euclid(deck.len() as i32) as usize,
+            None => 0,
+        };
+        state.selected = deck.get(next).copied();
+    }
+
     let bounds = get_viewport_bounds(context);
     let center = 0.5 * (bounds.0 + bounds.1);
     let w = DECK_BUTTON_W + GAP;
@@ -68,7 +80,8 @@ pub fn deck_draw(
             Vector2f::new(ACTION_BUTTON_W, BUTTON_SIZE),
             0,
         )
-        .with_span(Span::new().with_text_borrowed("Remove"));
+        .with_span(Span::new().with_text_borrowed("Remove"))
+        .with_focus(true);
         confirm.draw(context, input_state);
 
         if confirm.clicked(input_state) {
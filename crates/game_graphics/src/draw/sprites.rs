@@ -123,6 +123,14 @@ pub(crate) fn place_unit_sprite(
     sprites.push(sprite);
 }
 
+/// Rebuilds sprites from scratch, eg. after the world has been restored.
+pub(crate) fn reset_unit_sprites(world: &World, sprites: &mut Vec<UnitSprite>) {
+    sprites.clear();
+    for (entity, position, _) in query_iter!(world, With(position, health)) {
+        place_unit_sprite(entity, *position, world, sprites);
+    }
+}
+
 pub(crate) fn remove_unit_sprite(entity: Entity, sprites: &mut Vec<UnitSprite>) {
     sprites.retain(|a| a.entity != entity);
 }
@@ -1,17 +1,18 @@
 use rogalik::prelude::*;
 use wunderkammer::prelude::*;
 
-use game_logic::World;
+use game_logic::{components::Tag, World};
 
 use crate::{
     globals::{
         BASE_TEXT_SIZE, BUTTON_CLICK_SHIFT, DECK_BUTTON_H, DECK_BUTTON_SPRITE,
         DECK_BUTTON_SPRITE_SELECTED, DECK_BUTTON_W, DIGITS_TEXT_SIZE, FOOD_COLOR, FOOD_ICON, GAP,
-        HEALTH_ICON, ICON_SIZE, RED_COLOR, SIDE_PANEL_W, SPRITE_SIZE, TEXT_LINE_GAP, UI_Z,
+        HEALTH_ICON, ICON_SIZE, INSPECT_PANEL_H, INSPECT_PANEL_W, PANEL_SPRTE, RED_COLOR,
+        SIDE_PANEL_W, SPRITE_SIZE, TEXT_LINE_GAP, UI_Z,
     },
     input::InputState,
     ui::{Button, Span, TextBox},
-    utils::get_viewport_bounds,
+    utils::{get_hit_margin, get_viewport_bounds},
 };
 
 use super::sprites::get_sprite_data;
@@ -111,7 +112,8 @@ pub(crate) fn draw_deck_button(
     input_state: &InputState,
 ) -> bool {
     let mut button = Button::new(origin, Vector2f::new(DECK_BUTTON_W, DECK_BUTTON_H), z)
-        .with_sprite("ui", DECK_BUTTON_SPRITE);
+        .with_sprite("ui", DECK_BUTTON_SPRITE)
+        .with_hit_margin(get_hit_margin(context));
     if selected {
         button = button.with_sprite("ui", DECK_BUTTON_SPRITE_SELECTED);
     }
@@ -183,26 +185,20 @@ pub(crate) fn draw_description(entity: Entity, name: &str, world: &World, contex
             .graphics
             .text_dimensions("default", name, BASE_TEXT_SIZE)
             .x;
-        let names: Vec<String> = tags.iter().map(|a| a.into()).collect();
-
-        let _ = context.graphics.draw_text(
-            "default",
-            &names.join(", "),
+        draw_tags(
+            tags,
             origin + Vector2f::new(name_w + GAP, 0.),
             UI_Z,
-            BASE_TEXT_SIZE,
-            SpriteParams {
-                color: RED_COLOR,
-                ..Default::default()
-            },
+            world,
+            context,
         );
     }
 
     let gap = TEXT_LINE_GAP * BASE_TEXT_SIZE;
     origin.y -= BASE_TEXT_SIZE + 2. * gap;
 
-    if let Some(descr) = &data.description {
-        let mut content = descr.to_string();
+    if let Some(descr) = data.get_description(&world.resources.data) {
+        let mut content = descr;
 
         if let Some(limit) = world.components.trigger_limit.get(entity) {
             content += &format!("Triggers max {}x/turn.", limit.default());
@@ -214,3 +210,113 @@ pub(crate) fn draw_description(entity: Entity, name: &str, world: &World, contex
         origin.y -= h - BASE_TEXT_SIZE;
     };
 }
+
+fn draw_tags(tags: &[Tag], origin: Vector2f, z: i32, world: &World, context: &mut Context) {
+    let mut origin = origin;
+    for tag in tags {
+        let mut span = Span::new();
+        if let Some(icon) = world
+            .resources
+            .data
+            .tags
+            .get(tag.name())
+            .and_then(|t| t.icon)
+        {
+            span = span
+                .with_sprite("icons_small", icon)
+                .with_spacer(2.)
+                .with_sprite_size(ICON_SIZE);
+        }
+        let span = span
+            .with_text_borrowed(world.resources.data.tag_name(tag.name()))
+            .with_spacer(GAP)
+            .with_text_size(BASE_TEXT_SIZE)
+            .with_text_color(RED_COLOR);
+        span.draw(origin, z, context);
+        origin.x += span.width(context);
+    }
+}
+
+/// Detailed, centered unit panel.
+pub(crate) fn draw_inspect_panel(entity: Entity, world: &World, context: &mut Context) {
+    let Some(name) = world.components.name.get(entity) else {
+        return;
+    };
+    let bounds = get_viewport_bounds(context);
+    let size = Vector2f::new(INSPECT_PANEL_W, INSPECT_PANEL_H);
+    let origin = (0.5 * (bounds.0 + bounds.1 - size)).round();
+
+    let _ = context.graphics.draw_atlas_sprite(
+        "ui",
+        PANEL_SPRTE,
+        origin,
+        UI_Z,
+        size,
+        SpriteParams {
+            slice: Some((4, Vector2f::splat(SPRITE_SIZE))),
+            ..Default::default()
+        },
+    );
+
+    let top = origin + Vector2f::new(GAP, size.y - GAP - SPRITE_SIZE);
+    if let Some(sprite) = get_sprite_data(name, world) {
+        let _ = context.graphics.draw_atlas_sprite(
+            &sprite.atlas,
+            sprite.index,
+            top,
+            UI_Z + 1,
+            Vector2f::splat(SPRITE_SIZE),
+            SpriteParams::default(),
+        );
+    }
+    draw_unit_stats(
+        entity,
+        top - Vector2f::new(0., DIGITS_TEXT_SIZE),
+        UI_Z + 1,
+        world,
+        context,
+    );
+
+    let mut text_origin = top + Vector2f::new(SPRITE_SIZE + GAP, SPRITE_SIZE - BASE_TEXT_SIZE);
+    let title = if world.components.npc.get(entity).is_some() {
+        format!("{} (enemy)", name)
+    } else {
+        name.to_string()
+    };
+    let _ = context.graphics.draw_text(
+        "default",
+        &title,
+        text_origin,
+        UI_Z + 1,
+        BASE_TEXT_SIZE,
+        SpriteParams::default(),
+    );
+
+    if let Some(tags) = world.components.tags.get(entity) {
+        text_origin.y -= BASE_TEXT_SIZE + GAP;
+        draw_tags(tags, text_origin, UI_Z + 1, world, context);
+    }
+
+    let Some(data) = world.resources.data.entities.get(name) else {
+        return;
+    };
+    let mut content = data
+        .get_description(&world.resources.data)
+        .unwrap_or_default();
+    if let Some(limit) = world.components.trigger_limit.get(entity) {
+        content += &format!("Triggers max {}x/turn.", limit.default());
+    }
+    if let Some(tier) = data.tier {
+        content += &format!("\nTier {}.", tier);
+    }
+
+    TextBox::owned(content).draw(
+        Vector2f::new(
+            origin.x + GAP,
+            top.y - DIGITS_TEXT_SIZE - GAP - BASE_TEXT_SIZE,
+        ),
+        size.x - 2. * GAP,
+        UI_Z + 1,
+        context,
+    );
+}
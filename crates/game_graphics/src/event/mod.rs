@@ -0,0 +1,52 @@
+use rogalik::prelude::*;
+use wunderkammer::prelude::*;
+
+use game_logic::{event::EventState, InputEvent, World};
+
+use crate::{
+    globals::{BASE_TEXT_SIZE, BUTTON_SIZE, GAP},
+    input::InputState,
+    ui::{Button, Span, TextBox},
+    utils::get_viewport_bounds,
+};
+
+#[derive(Default)]
+pub struct EventGraphics {
+    pub input_queue: ObservableQueue<InputEvent>,
+}
+
+pub fn event_draw(
+    logic_state: &EventState,
+    state: &mut EventGraphics,
+    world: &World,
+    context: &mut Context,
+    input_state: &InputState,
+) {
+    crate::utils::draw_background(context);
+
+    let Some(data) = logic_state
+        .event
+        .as_ref()
+        .and_then(|n| world.resources.data.events.get(n))
+    else {
+        return;
+    };
+
+    let bounds = get_viewport_bounds(context);
+    let width = bounds.1.x - bounds.0.x - 2. * GAP;
+    let mut origin = Vector2f::new(bounds.0.x + GAP, bounds.1.y - BASE_TEXT_SIZE - GAP);
+
+    let h = TextBox::borrowed(&data.text).draw(origin, width, 0, context);
+    origin.y -= h + BUTTON_SIZE;
+
+    for (i, option) in data.options.iter().enumerate() {
+        let button = Button::new(origin, Vector2f::new(width, BUTTON_SIZE), 0)
+            .with_span(Span::new().with_text_borrowed(&option.text));
+        button.draw(context, input_state);
+
+        if button.clicked(input_state) {
+            state.input_queue.push(InputEvent::PickOption(i));
+        }
+        origin.y -= BUTTON_SIZE + GAP;
+    }
+}
@@ -9,11 +9,16 @@ pub const SPRITE_OFFSET: Vector2f = Vector2f {
 
 pub const BUTTON_SIZE: f32 = 0.5 * SPRITE_SIZE + GAP;
 pub const BUTTON_CLICK_SHIFT: f32 = 2.;
+pub const FOCUS_BORDER: f32 = 1.;
 pub const DECK_BUTTON_H: f32 = SPRITE_SIZE + 4. * GAP;
 pub const DECK_BUTTON_W: f32 = SPRITE_SIZE;
 pub const ACTION_BUTTON_W: f32 = (game_logic::globals::DECK_SIZE / 2) as f32 * DECK_BUTTON_W
     + (game_logic::globals::DECK_SIZE / 2 - 1) as f32 * GAP;
 pub const SIDE_PANEL_W: f32 = ACTION_BUTTON_W + GAP;
+// Below this viewport width touch targets are enlarged
+pub const SMALL_VIEWPORT_W: f32 = 360.;
+pub const INSPECT_PANEL_W: f32 = 160.;
+pub const INSPECT_PANEL_H: f32 = 112.;
 
 pub const BUBBLE_Z: i32 = 150;
 pub const OVERLAY_Z: i32 = 100;
@@ -35,6 +40,7 @@ pub const BUBBLE_WAIT_AGE: f32 = 0.25;
 pub const MOVE_SPEED: f32 = 6. * TILE_SIZE;
 pub const MOVE_THRESH: f32 = 0.1;
 pub const DISINTEGRATE_SPEED: f32 = 2.;
+pub const GHOST_ALPHA: u8 = 128;
 
 // PALETTE
 
@@ -2,9 +2,17 @@ use rogalik::math::vectors::{Vector2f, Vector2i};
 
 #[derive(Default)]
 pub struct InputState {
-    // pub dir: Option<Vector2i>,
+    pub dir: Option<Vector2i>,
     pub mouse_world_position: Vector2f,
     pub click: ButtonState,
+    pub inspect: bool,
+    // Keyboard
+    pub digit: Option<usize>,
+    pub action: bool,
+    pub fight: bool,
+    pub undo: bool,
+    // Toggles debug overlays
+    pub debug: bool,
 }
 
 #[derive(Clone, Copy, Default, Debug, PartialEq)]
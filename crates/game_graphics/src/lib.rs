@@ -1,8 +1,12 @@
 pub mod battle;
 pub mod deck;
 mod draw;
+pub mod event;
 pub mod globals;
 pub mod input;
+pub mod map;
+pub mod menu;
 pub mod shop;
 mod ui;
 pub mod utils;
+pub mod village;
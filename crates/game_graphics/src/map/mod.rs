@@ -0,0 +1,166 @@
+use rogalik::prelude::*;
+use wunderkammer::prelude::*;
+
+use game_logic::{
+    globals::MAP_W,
+    map::{MapNode, NodeKind},
+    InputEvent, World,
+};
+
+use crate::{
+    globals::{
+        BASE_TEXT_SIZE, BUTTON_SIZE, BUTTON_SPRITE_SELECTED, BUTTON_TEXT_COLOR, FIGHT_ICON, GAP,
+        HEALTH_ICON, ICON_SIZE, RED_COLOR, UNIT_ICON,
+    },
+    input::InputState,
+    ui::{Button, Span},
+    utils::get_viewport_bounds,
+};
+
+const PATH_DOTS: usize = 3;
+
+#[derive(Default)]
+pub struct MapGraphics {
+    pub input_queue: ObservableQueue<InputEvent>,
+}
+
+pub fn map_draw(
+    state: &mut MapGraphics,
+    world: &World,
+    context: &mut Context,
+    input_state: &InputState,
+) {
+    crate::utils::draw_background(context);
+
+    let map = &world.resources.map;
+    let bounds = get_viewport_bounds(context);
+    let next_row = map.next_row();
+
+    draw_paths(world, context);
+
+    let mut hovered = None;
+    for (row, nodes) in map.rows.iter().enumerate() {
+        for (i, node) in nodes.iter().enumerate() {
+            let visited = map.current == Some((row, i));
+            let button = node_button(row, node, visited, map.rows.len(), context);
+            button.draw(context, input_state);
+
+            if button.mouse_over(input_state) {
+                hovered = Some(node.kind);
+            }
+            if row == next_row && map.is_reachable(i) && button.clicked(input_state) {
+                state.input_queue.push(InputEvent::PickNode(i));
+            }
+        }
+    }
+
+    let _ = context.graphics.draw_text(
+        "default",
+        &format!(
+            "Day {}/{}. Town health: {}/{}.",
+            world.resources.player_data.level,
+            map.rows.len(),
+            world.resources.player_data.health,
+            world.resources.player_data.max_health
+        ),
+        bounds.0 + Vector2f::splat(GAP),
+        0,
+        BASE_TEXT_SIZE,
+        SpriteParams::default(),
+    );
+
+    if let Some(kind) = hovered {
+        let _ = context.graphics.draw_text(
+            "default",
+            get_node_description(kind),
+            Vector2f::new(bounds.0.x + GAP, bounds.1.y - BASE_TEXT_SIZE - GAP),
+            0,
+            BASE_TEXT_SIZE,
+            SpriteParams::default(),
+        );
+    }
+}
+
+fn node_button<'a>(
+    row: usize,
+    node: &MapNode,
+    visited: bool,
+    depth: usize,
+    context: &Context,
+) -> Button<'a> {
+    let mut span = Span::new().with_sprite_size(ICON_SIZE);
+    span = match node.kind {
+        NodeKind::Battle => span.with_sprite("icons_small", FIGHT_ICON),
+        NodeKind::Elite => span
+            .with_sprite("icons_small", FIGHT_ICON)
+            .with_sprite_color(RED_COLOR),
+        NodeKind::Shop => span.with_sprite("icons_small", UNIT_ICON),
+        NodeKind::Rest => span.with_sprite("icons_small", HEALTH_ICON),
+        NodeKind::Event => span.with_text_borrowed("?"),
+    };
+
+    let mut button = Button::new(
+        get_node_origin(row, node.column, depth, context),
+        Vector2f::splat(BUTTON_SIZE),
+        0,
+    )
+    .with_span(span);
+    if visited {
+        button = button.with_sprite("ui", BUTTON_SPRITE_SELECTED);
+    }
+    button
+}
+
+fn draw_paths(world: &World, context: &mut Context) {
+    let map = &world.resources.map;
+    let depth = map.rows.len();
+    let offset = Vector2f::splat(0.5 * BUTTON_SIZE);
+
+    for (row, nodes) in map.rows.iter().enumerate().take(depth.saturating_sub(1)) {
+        for node in nodes.iter() {
+            let a = get_node_origin(row, node.column, depth, context) + offset;
+            for &next in node.next.iter() {
+                let b = get_node_origin(row + 1, map.rows[row + 1][next].column, depth, context)
+                    + offset;
+                for i in 1..=PATH_DOTS {
+                    let t = i as f32 / (PATH_DOTS + 1) as f32;
+                    let _ = context.graphics.draw_text(
+                        "default",
+                        ".",
+                        a.lerp(&b, t).round(),
+                        0,
+                        BASE_TEXT_SIZE,
+                        SpriteParams {
+                            color: BUTTON_TEXT_COLOR,
+                            ..Default::default()
+                        },
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn get_node_origin(row: usize, column: usize, depth: usize, context: &Context) -> Vector2f {
+    let bounds = get_viewport_bounds(context);
+    let center = 0.5 * (bounds.0 + bounds.1);
+    let w = bounds.1.x - bounds.0.x - 2. * GAP - BUTTON_SIZE;
+    let x_step = w / depth.saturating_sub(1).max(1) as f32;
+    let y_step = BUTTON_SIZE + 2. * GAP;
+
+    Vector2f::new(
+        bounds.0.x + GAP + row as f32 * x_step,
+        center.y + (column as f32 - 0.5 * (MAP_W - 1) as f32) * y_step - 0.5 * BUTTON_SIZE,
+    )
+    .round()
+}
+
+fn get_node_description(kind: NodeKind) -> &'static str {
+    match kind {
+        NodeKind::Battle => "Battle: defend the town.",
+        NodeKind::Elite => "Elite: stronger waves, better rewards.",
+        NodeKind::Shop => "Market: recruit a unit from a better selection.",
+        NodeKind::Rest => "Rest: repair the town.",
+        NodeKind::Event => "Unknown: who knows what awaits...",
+    }
+}
@@ -0,0 +1,166 @@
+use rogalik::prelude::*;
+
+use game_logic::{
+    difficulty::{Difficulty, MAX_DIFFICULTY},
+    profile::Profile,
+};
+
+use crate::{
+    globals::{ACTION_BUTTON_W, BASE_TEXT_SIZE, BUTTON_SIZE, FIGHT_ICON, GAP, ICON_SIZE},
+    input::InputState,
+    ui::{Button, Span},
+    utils::get_viewport_bounds,
+};
+
+const STATS_UNIT_ROWS: usize = 8;
+
+pub enum MenuAction {
+    Start,
+    ChangeDifficulty(Difficulty),
+    Stats,
+}
+
+pub fn main_menu_draw(
+    difficulty: Difficulty,
+    profile: &Profile,
+    context: &mut Context,
+    input_state: &InputState,
+) -> Option<MenuAction> {
+    let bounds = get_viewport_bounds(context);
+    let mut action = None;
+
+    let start = Button::new(
+        Vector2f::new(bounds.1.x - ACTION_BUTTON_W - GAP, bounds.0.y + GAP),
+        Vector2f::new(ACTION_BUTTON_W, BUTTON_SIZE),
+        0,
+    )
+    .with_span(
+        Span::new()
+            .with_sprite("icons_small", FIGHT_ICON)
+            .with_spacer(2.)
+            .with_text_borrowed("Start")
+            .with_sprite_size(ICON_SIZE),
+    );
+    start.draw(context, input_state);
+    if start.clicked(input_state) {
+        action = Some(MenuAction::Start);
+    }
+
+    let stats = Button::new(
+        Vector2f::new(
+            bounds.1.x - ACTION_BUTTON_W - GAP,
+            bounds.0.y + 2. * GAP + BUTTON_SIZE,
+        ),
+        Vector2f::new(ACTION_BUTTON_W, BUTTON_SIZE),
+        0,
+    )
+    .with_span(Span::new().with_text_borrowed("Stats"));
+    stats.draw(context, input_state);
+    if stats.clicked(input_state) {
+        action = Some(MenuAction::Stats);
+    }
+
+    // Difficulty selector
+    let mut origin = bounds.0 + Vector2f::splat(GAP);
+    if difficulty.0 > 0 {
+        let prev = Button::new(origin, Vector2f::splat(BUTTON_SIZE), 0)
+            .with_span(Span::new().with_text_borrowed("<"));
+        prev.draw(context, input_state);
+        if prev.clicked(input_state) {
+            action = Some(MenuAction::ChangeDifficulty(Difficulty(difficulty.0 - 1)));
+        }
+    }
+    origin.x += BUTTON_SIZE + GAP;
+
+    let label = Span::new().with_text_owned(format!("Difficulty {}", difficulty.0));
+    let label_w = label.width(context);
+    label.draw(
+        origin + Vector2f::new(0., 0.5 * (BUTTON_SIZE - label.height())),
+        0,
+        context,
+    );
+    origin.x += label_w + GAP;
+
+    let next = Difficulty(difficulty.0 + 1);
+    if next.0 <= MAX_DIFFICULTY && profile.is_unlocked(next) {
+        let button = Button::new(origin, Vector2f::splat(BUTTON_SIZE), 0)
+            .with_span(Span::new().with_text_borrowed(">"));
+        button.draw(context, input_state);
+        if button.clicked(input_state) {
+            action = Some(MenuAction::ChangeDifficulty(next));
+        }
+    }
+
+    // Active modifiers
+    let mut origin = bounds.0 + Vector2f::new(GAP, 2. * GAP + BUTTON_SIZE);
+    for modifier in difficulty.modifiers().iter().rev() {
+        let _ = context.graphics.draw_text(
+            "default",
+            &String::from(modifier),
+            origin,
+            0,
+            BASE_TEXT_SIZE,
+            SpriteParams::default(),
+        );
+        origin.y += BASE_TEXT_SIZE + GAP;
+    }
+
+    action
+}
+
+/// Returns true when the player wants to go back.
+pub fn stats_draw(profile: &Profile, context: &mut Context, input_state: &InputState) -> bool {
+    let bounds = get_viewport_bounds(context);
+    let line = BASE_TEXT_SIZE + GAP;
+    let mut origin = Vector2f::new(bounds.0.x + GAP, bounds.1.y - BASE_TEXT_SIZE - GAP);
+
+    let mut lines = vec![
+        format!("Runs played: {}", profile.runs.len()),
+        format!("Runs won: {}", profile.wins()),
+        format!("Best day reached: {}", profile.best_level),
+        format!("Units unlocked: {}", profile.unlocked_units.len()),
+    ];
+    if let Some(last) = profile.runs.last() {
+        lines.push(format!(
+            "Last run: day {} at difficulty {}, {}",
+            last.level,
+            last.difficulty,
+            if last.won { "won" } else { "lost" }
+        ));
+    }
+    lines.push(String::new());
+
+    // Most played units first
+    let mut units = profile.units.iter().collect::<Vec<_>>();
+    units.sort_by(|a, b| b.1.runs.cmp(&a.1.runs).then_with(|| a.0.cmp(b.0)));
+    for (name, record) in units.iter().take(STATS_UNIT_ROWS) {
+        lines.push(format!(
+            "{}: won {}/{} ({:.0}%)",
+            name,
+            record.wins,
+            record.runs,
+            100. * record.win_rate()
+        ));
+    }
+
+    for text in lines {
+        let _ = context.graphics.draw_text(
+            "default",
+            &text,
+            origin,
+            0,
+            BASE_TEXT_SIZE,
+            SpriteParams::default(),
+        );
+        origin.y -= line;
+    }
+
+    let back = Button::new(
+        Vector2f::new(bounds.1.x - ACTION_BUTTON_W - GAP, bounds.0.y + GAP),
+        Vector2f::new(ACTION_BUTTON_W, BUTTON_SIZE),
+        0,
+    )
+    .with_span(Span::new().with_text_borrowed("Back"));
+    back.draw(context, input_state);
+    back.clicked(input_state)
+}
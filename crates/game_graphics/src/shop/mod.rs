@@ -26,6 +26,10 @@ pub fn shop_draw(
 ) {
     crate::utils::draw_background(context);
 
+    if let Some(dir) = input_state.dir {
+        state.selected = next_choice(state.selected, dir.x, logic_state);
+    }
+
     let bounds = get_viewport_bounds(context);
     let center = 0.5 * (bounds.0 + bounds.1);
     let w = 2. * DECK_BUTTON_W + GAP;
@@ -94,7 +98,8 @@ pub fn shop_draw(
             Vector2f::new(ACTION_BUTTON_W, BUTTON_SIZE),
             0,
         )
-        .with_span(Span::new().with_text_borrowed("Pick"));
+        .with_span(Span::new().with_text_borrowed("Pick"))
+        .with_focus(true);
         confirm.draw(context, input_state);
 
         if confirm.clicked(input_state) {
@@ -102,3 +107,19 @@ pub fn shop_draw(
         }
     }
 }
+
+fn next_choice(current: Option<usize>, step: i32, logic_state: &ShopState) -> Option<usize> {
+    let available = (0..logic_state.choices.len())
+        .filter(|i| logic_state.choices[*i].is_some())
+        .collect::<Vec<_>>();
+    if available.is_empty() {
+        return None;
+    }
+    let Some(idx) = current.and_then(|c| available.iter().position(|i| *i == c)) else {
+        return available.first().copied();
+    };
+    let len = available.len() as i32;
+    available
+        .get((idx as i32 + step).rem_euclid(len) as usize)
+        .copied()
+}
@@ -2,7 +2,7 @@ use rogalik::prelude::*;
 
 use super::Span;
 use crate::{
-    globals::{BUTTON_CLICK_SHIFT, BUTTON_SPRITE, SPRITE_SIZE},
+    globals::{BUTTON_CLICK_SHIFT, BUTTON_SPRITE, FOCUS_BORDER, RED_COLOR, SPRITE_SIZE},
     input::{ButtonState, InputState},
 };
 
@@ -15,6 +15,10 @@ pub struct Button<'a> {
     sprite_index: usize,
     span: Option<Span<'a>>,
     slice: Option<(usize, Vector2f)>,
+    // Keyboard focus
+    focused: bool,
+    // Extends the clickable area beyond the sprite
+    hit_margin: f32,
 }
 impl<'a> Button<'a> {
     pub fn new(origin: Vector2f, size: Vector2f, z: i32) -> Self {
@@ -26,6 +30,8 @@ impl<'a> Button<'a> {
             sprite_index: BUTTON_SPRITE,
             span: None,
             slice: Some((8, Vector2f::splat(SPRITE_SIZE))),
+            focused: false,
+            hit_margin: 0.,
         }
     }
     pub fn with_span(mut self, span: Span<'a>) -> Self {
@@ -37,6 +43,14 @@ impl<'a> Button<'a> {
         self.sprite_index = index;
         self
     }
+    pub fn with_focus(mut self, focused: bool) -> Self {
+        self.focused = focused;
+        self
+    }
+    pub fn with_hit_margin(mut self, margin: f32) -> Self {
+        self.hit_margin = margin;
+        self
+    }
     // pub fn with_slice(mut self, slice: (usize, Vector2f)) -> Self {
     //     self.slice = Some(slice);
     //     self
@@ -52,6 +66,22 @@ impl<'a> Button<'a> {
             let _ = context.audio.play("click", false);
         }
 
+        if self.focused {
+            // Highlight frame behind the button
+            let _ = context.graphics.draw_atlas_sprite(
+                self.sprite_atlas,
+                self.sprite_index,
+                self.origin - Vector2f::splat(FOCUS_BORDER),
+                self.z - 1,
+                self.size + Vector2f::splat(2. * FOCUS_BORDER),
+                SpriteParams {
+                    color: RED_COLOR,
+                    slice: self.slice,
+                    ..Default::default()
+                },
+            );
+        }
+
         let _ = context.graphics.draw_atlas_sprite(
             self.sprite_atlas,
             idx,
@@ -73,7 +103,8 @@ impl<'a> Button<'a> {
     }
 
     pub fn clicked(&self, state: &InputState) -> bool {
-        state.click == ButtonState::Released && self.mouse_over(state)
+        (state.click == ButtonState::Released && self.mouse_over(state))
+            || (self.focused && state.action)
     }
 
     pub fn pressed(&self, state: &InputState) -> bool {
@@ -81,6 +112,10 @@ impl<'a> Button<'a> {
     }
 
     pub fn mouse_over(&self, state: &InputState) -> bool {
-        crate::utils::is_mouse_over(self.origin, self.size, state)
+        crate::utils::is_mouse_over(
+            self.origin - Vector2f::splat(self.hit_margin),
+            self.size + Vector2f::splat(2. * self.hit_margin),
+            state,
+        )
     }
 }
@@ -1,4 +1,4 @@
-use crate::globals::{BACKGROUND_Z, SPRITE_OFFSET, TILE_SIZE, TILE_Z};
+use crate::globals::{BACKGROUND_Z, GAP, SMALL_VIEWPORT_W, SPRITE_OFFSET, TILE_SIZE, TILE_Z};
 use rogalik::prelude::*;
 
 use game_logic::{
@@ -11,6 +11,16 @@ pub fn get_viewport_bounds(context: &Context) -> (Vector2f, Vector2f) {
     camera.get_bounds()
 }
 
+/// Extra clickable area around densely packed buttons, for touch screens.
+pub(crate) fn get_hit_margin(context: &Context) -> f32 {
+    let bounds = get_viewport_bounds(context);
+    if bounds.1.x - bounds.0.x < SMALL_VIEWPORT_W {
+        0.5 * GAP
+    } else {
+        0.
+    }
+}
+
 pub fn tile_to_world(p: Position) -> Vector2f {
     Vector2f::new(
         0.5 * TILE_SIZE * p.x as f32 - 0.5 * TILE_SIZE * p.y as f32,
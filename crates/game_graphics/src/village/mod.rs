@@ -0,0 +1,78 @@
+use rogalik::prelude::*;
+use wunderkammer::prelude::*;
+
+use game_logic::{village::VillageState, InputEvent, World};
+
+use crate::{
+    globals::{BASE_TEXT_SIZE, BUTTON_SIZE, GAP},
+    input::InputState,
+    ui::{Button, Span, TextBox},
+    utils::get_viewport_bounds,
+};
+
+const VILLAGE_BUTTON_W: f32 = 64.;
+
+#[derive(Default)]
+pub struct VillageGraphics {
+    pub input_queue: ObservableQueue<InputEvent>,
+}
+
+pub fn village_draw(
+    logic_state: &VillageState,
+    state: &mut VillageGraphics,
+    world: &World,
+    context: &mut Context,
+    input_state: &InputState,
+) {
+    crate::utils::draw_background(context);
+
+    let bounds = get_viewport_bounds(context);
+    let width = bounds.1.x - bounds.0.x - 3. * GAP - VILLAGE_BUTTON_W;
+    let mut origin = Vector2f::new(bounds.0.x + GAP, bounds.1.y - BASE_TEXT_SIZE - GAP);
+
+    let _ = context.graphics.draw_text(
+        "default",
+        "Choose your village:",
+        origin,
+        0,
+        BASE_TEXT_SIZE,
+        SpriteParams::default(),
+    );
+    origin.y -= BUTTON_SIZE + GAP;
+
+    for (i, name) in logic_state.choices.iter().enumerate() {
+        let Some(data) = world.resources.data.villages.get(name) else {
+            continue;
+        };
+
+        let button = Button::new(origin, Vector2f::new(VILLAGE_BUTTON_W, BUTTON_SIZE), 0)
+            .with_span(Span::new().with_text_borrowed(name));
+        button.draw(context, input_state);
+
+        if button.clicked(input_state) {
+            state.input_queue.push(InputEvent::PickOption(i));
+        }
+
+        let units = data
+            .units
+            .iter()
+            .map(String::from)
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut text = format!("Town health: {}. Units: {}.", data.health, units);
+        if let Some(description) = &data.description {
+            text += &format!("\n{}", description.trim());
+        }
+
+        let h = TextBox::owned(text).draw(
+            Vector2f::new(
+                origin.x + VILLAGE_BUTTON_W + GAP,
+                origin.y + BUTTON_SIZE - BASE_TEXT_SIZE,
+            ),
+            width,
+            0,
+            context,
+        );
+        origin.y -= h.max(BUTTON_SIZE) + GAP;
+    }
+}
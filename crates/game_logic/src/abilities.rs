@@ -0,0 +1,64 @@
+use wunderkammer::prelude::*;
+
+use game_data::{
+    abilities::{Effect, Target},
+    AbilityData,
+};
+
+use crate::{commands::RuneCommand, components::Tile, utils::get_tile_at, world::Ent, World};
+
+/// Engine-side equivalent of an ability script.
+pub(crate) fn run_ability(
+    ability: &AbilityData,
+    entity: Entity,
+    world: &World,
+) -> Vec<RuneCommand> {
+    if let Some(tile) = &ability.tile {
+        if !is_on_tile(entity, tile, world) {
+            return Vec::new();
+        }
+    }
+
+    match ability.effect {
+        Effect::Health => get_targets(&ability.target, entity, world)
+            .into_iter()
+            .map(|e| RuneCommand::ChangeHealth(e, ability.amount))
+            .collect(),
+        Effect::Food => vec![RuneCommand::ChangeFood(ability.amount, Some(entity.into()))],
+        Effect::TownHealth => vec![RuneCommand::ChangeTownHealth(ability.amount)],
+    }
+}
+
+fn get_targets(target: &Target, entity: Entity, world: &World) -> Vec<Ent> {
+    match target {
+        Target::Host => vec![entity.into()],
+        Target::AllPlayers => query_iter!(world.0, With(player, position))
+            .map(|(e, _, _)| e.into())
+            .collect(),
+        Target::AllPlayersWithTag(tag) => world.get_players_with_tag(tag),
+        Target::InFront => world
+            .get_player_in_front(&entity.into())
+            .into_iter()
+            .collect(),
+        Target::Adjacent => world.get_adjacent_players(&entity.into()),
+        Target::Column => match world.0.components.position.get(entity) {
+            Some(position) => world.get_players_in_column(position.x),
+            None => Vec::new(),
+        },
+    }
+}
+
+fn is_on_tile(entity: Entity, tile: &str, world: &World) -> bool {
+    let Ok(tile) = serde_yaml::from_str::<Tile>(tile) else {
+        log::error!("Unknown tile: {}", tile);
+        return false;
+    };
+    world
+        .0
+        .components
+        .position
+        .get(entity)
+        .and_then(|p| get_tile_at(world, *p))
+        .and_then(|e| world.0.components.tile.get(e))
+        == Some(&tile)
+}
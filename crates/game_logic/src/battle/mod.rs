@@ -1,15 +1,27 @@
+use rand::prelude::*;
 use std::collections::VecDeque;
 use wunderkammer::prelude::*;
 
-use crate::{commands, events::InputEvent, globals::WAVE_COUNT, GameEnv};
+use crate::{
+    commands, events::InputEvent, globals::WAVE_COUNT, scripting::ScriptRng, utils::can_place_unit,
+    world::WorldInner, GameEnv,
+};
 
 pub(crate) mod board;
 mod npcs;
 pub(crate) mod player;
-mod systems;
+mod preview;
+pub(crate) mod synergies;
+pub(crate) mod systems;
 pub(crate) mod utils;
 
-#[derive(Default)]
+#[cfg(test)]
+mod tests;
+
+pub use preview::BattlePreview;
+pub use synergies::{get_reached_synergies, get_tag_count};
+
+#[derive(Clone, Default)]
 pub enum BattleMode {
     #[default]
     Plan,
@@ -17,17 +29,30 @@ pub enum BattleMode {
     Done,
 }
 
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct BattleState {
     on_fight_queue: VecDeque<Entity>,
     pub mode: BattleMode,
     pub wave: u32,
+    // Recomputed when the plan changes
+    pub preview: Option<BattlePreview>,
+    // Synergies activated on fight start
+    pub synergies: Vec<String>,
+    // World snapshots taken before each planning action
+    undo_stack: Vec<WorldInner>,
+    // Set by a new wave, the trigger runs once the spawned npcs are on the board
+    wave_start_pending: bool,
+}
+impl BattleState {
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
 }
 
 pub fn battle_init(env: &mut GameEnv) {
     env.world.resources.battle_state.mode = BattleMode::default();
     env.world.resources.battle_state.wave = 0;
-    env.world.resources.player_data.level += 1;
+    env.world.resources.script_rng = ScriptRng::new(thread_rng().gen());
 
     board::tiles_init(env);
     player::player_battle_init(&mut env.world);
@@ -37,6 +62,8 @@ pub fn battle_init(env: &mut GameEnv) {
 pub fn battle_exit(env: &mut GameEnv) {
     // purge pending commands
     while handle_command_queue(env) {}
+    systems::handle_board_trigger("on_battle_end", env);
+    while handle_command_queue(env) {}
     player::player_battle_exit(&mut env.world);
     board::clear_board(env);
 }
@@ -46,6 +73,11 @@ pub fn battle_update(env: &mut GameEnv) {
         return;
     };
 
+    if std::mem::take(&mut env.world.resources.battle_state.wave_start_pending) {
+        systems::handle_board_trigger("on_wave_start", env);
+        return;
+    }
+
     if systems::handle_killed(env) {
         return;
     }
@@ -57,6 +89,9 @@ pub fn battle_update(env: &mut GameEnv) {
 
     match env.world.resources.battle_state.mode {
         BattleMode::Plan => {
+            if env.world.resources.battle_state.preview.is_none() {
+                env.world.resources.battle_state.preview = Some(preview::get_preview(env));
+            }
             handle_input_events(env);
         }
         BattleMode::Fight => {
@@ -72,6 +107,9 @@ pub fn battle_update(env: &mut GameEnv) {
 }
 
 fn next_turn(env: &mut GameEnv) {
+    if env.world.resources.battle_state.wave > 0 {
+        systems::handle_board_trigger("on_wave_end", env);
+    }
     if env.world.resources.battle_state.wave >= WAVE_COUNT {
         if systems::check_win(env) {
             env.world.resources.game_mode = crate::GameMode::Win;
@@ -81,23 +119,27 @@ fn next_turn(env: &mut GameEnv) {
     }
     env.world.resources.battle_state.wave += 1;
     env.world.resources.battle_state.mode = BattleMode::Plan;
+    env.world.resources.battle_state.undo_stack.clear();
+    env.world.resources.battle_state.preview = None;
+    env.world.resources.battle_state.synergies.clear();
     player::player_next_turn(env);
     systems::reset_trigger_limits(&mut env.world);
     npcs::next_wave(env);
+    env.world.resources.battle_state.wave_start_pending = true;
 }
 
 fn fight_start(env: &mut GameEnv) {
     // Change battle mode
     env.world.resources.battle_state.mode = BattleMode::Fight;
+    env.world.resources.battle_state.undo_stack.clear();
+    env.world.resources.battle_state.preview = None;
 
-    // Collect on fight queue
-    let mut on_fight = query_iter!(env.world, With(position, on_fight))
-        .map(|(e, p, s)| (e, *p, s.to_string()))
-        .collect::<Vec<_>>();
+    systems::handle_village_passive(env);
+    synergies::synergies_start(env);
 
-    // Apply consistent front to back order.
-    on_fight.sort_by(|a, b| b.1.y.cmp(&a.1.y).then_with(|| a.1.x.cmp(&b.1.x)));
-    env.world.resources.battle_state.on_fight_queue = on_fight.iter().map(|(e, _, _)| *e).collect();
+    // Collect on fight queue
+    env.world.resources.battle_state.on_fight_queue =
+        systems::get_trigger_hosts("on_fight", &env.world).into();
 }
 
 fn handle_command_queue(env: &mut GameEnv) -> bool {
@@ -107,11 +149,23 @@ fn handle_command_queue(env: &mut GameEnv) -> bool {
 fn handle_input_events(env: &mut GameEnv) -> Option<()> {
     while let Some(event) = env.input.as_ref().unwrap().next() {
         match event {
+            // Only accepted actions leave an undo entry
             InputEvent::SummonPlayer(entity, target) => {
-                env.scheduler.send(commands::SummonPlayer(entity, target));
+                if can_place_unit(&env.world, entity, target) {
+                    push_undo(env);
+                    env.scheduler.send(commands::SummonPlayer(entity, target));
+                }
             }
             InputEvent::MoveUnit(entity, target) => {
-                env.scheduler.send(commands::MoveUnit(entity, target));
+                if can_place_unit(&env.world, entity, target) {
+                    push_undo(env);
+                    env.scheduler.send(commands::MoveUnit(entity, target));
+                }
+            }
+            InputEvent::Undo => {
+                if env.world.resources.battle_state.can_undo() {
+                    env.scheduler.send(commands::Undo);
+                }
             }
             InputEvent::Done => {
                 fight_start(env);
@@ -121,3 +175,40 @@ fn handle_input_events(env: &mut GameEnv) -> Option<()> {
     }
     Some(())
 }
+
+fn push_undo(env: &mut GameEnv) {
+    env.world.resources.battle_state.preview = None;
+
+    let snapshot = snapshot(&mut env.world);
+    env.world.resources.battle_state.undo_stack.push(snapshot);
+}
+
+/// Copy of the world without the undo stack and the (static) game data.
+fn snapshot(world: &mut crate::World) -> WorldInner {
+    // Take the stack out, so the snapshot does not contain the previous ones
+    let stack = std::mem::take(&mut world.resources.battle_state.undo_stack);
+    let data = std::mem::take(&mut world.resources.data);
+    let snapshot = world.0.clone();
+    world.resources.battle_state.undo_stack = stack;
+    world.resources.data = data;
+    snapshot
+}
+
+/// Restores the world to the state before the last planning action.
+pub(crate) fn pop_undo(world: &mut crate::World) -> bool {
+    let Some(snapshot) = world.resources.battle_state.undo_stack.pop() else {
+        return false;
+    };
+    let stack = std::mem::take(&mut world.resources.battle_state.undo_stack);
+    let vm = world.resources.vm.take();
+    let data = std::mem::take(&mut world.resources.data);
+    let trace = std::mem::take(&mut world.resources.trace);
+
+    world.0 = snapshot;
+    world.resources.battle_state.undo_stack = stack;
+    world.resources.vm = vm;
+    world.resources.data = data;
+    world.resources.trace = trace;
+    world.resources.battle_state.preview = None;
+    true
+}
@@ -15,7 +15,15 @@ pub(crate) fn next_wave(env: &mut GameEnv) {
 
     // let target_score = (0.5 * tier as f32 + (1.5 * wave as f32 - 1.)).ceil() as
     // u32;
-    let target_score = ((0.75 * tier as f32).floor() + (1.5 * wave as f32 - 1.)).ceil() as u32;
+    let target_score = ((0.75 * tier as f32).floor() + (1.5 * wave as f32 - 1.)).ceil() as u32
+        + env
+            .world
+            .resources
+            .map
+            .current_kind()
+            .map(|k| k.wave_score_bonus())
+            .unwrap_or(0)
+        + env.world.resources.difficulty.wave_score_bonus();
     // println!("T: {}, W: {}, S:{}", tier, wave, target_score);
     let mut score = 0;
 
@@ -42,6 +50,11 @@ pub(crate) fn next_wave(env: &mut GameEnv) {
         score += entity_score;
         env.world.0.components.npc.insert(entity, ());
 
+        let health_bonus = env.world.resources.difficulty.npc_health_bonus();
+        if let Some(health) = env.world.0.components.health.get_mut(entity) {
+            health.add_default(health_bonus);
+        }
+
         let layout_weights = layout
             .iter()
             .enumerate()
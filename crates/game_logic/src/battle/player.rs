@@ -13,7 +13,11 @@ pub(super) fn player_battle_exit(world: &mut World) {
         .collect::<Vec<_>>();
 
     for entity in placed {
-        remove_player_from_board(entity, world);
+        if world.components.summoned.get(entity).is_some() {
+            world.despawn(entity);
+        } else {
+            remove_player_from_board(entity, world);
+        }
     }
 }
 
@@ -22,7 +26,7 @@ pub(super) fn player_next_turn(env: &mut GameEnv) {
     let food_gain = match env.world.resources.battle_state.wave {
         1 | 2 => 3,
         _ => 4,
-    };
+    } - env.world.resources.difficulty.food_penalty();
     env.world.0.resources.player_data.food += food_gain;
 }
 
@@ -31,6 +35,7 @@ pub(crate) fn reset_player(entity: Entity, world: &mut World) {
         health.restore();
     }
     world.components.killed.remove(entity);
+    world.components.script_state.remove(entity);
 }
 
 pub(crate) fn remove_player_from_board(entity: Entity, world: &mut World) {
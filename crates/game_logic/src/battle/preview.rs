@@ -0,0 +1,94 @@
+use wunderkammer::prelude::*;
+
+use crate::{commands, GameEnv, World};
+
+// Safety net against scripts that keep re-triggering each other
+const MAX_PREVIEW_STEPS: usize = 1000;
+
+/// Expected result of the upcoming fight.
+#[derive(Clone, Default)]
+pub struct BattlePreview {
+    pub deaths: Vec<Entity>,
+    pub town_health: i32,
+    pub food: i32,
+}
+
+/// Runs the upcoming fight on a copy of the world.
+/// Scripts roll from the seeded `ScriptRng`, which is cloned with the world,
+/// so random effects match the actual fight.
+pub(crate) fn get_preview(env: &mut GameEnv) -> BattlePreview {
+    let mut sim = GameEnv {
+        world: World(super::snapshot(&mut env.world)),
+        ..Default::default()
+    };
+    sim.world.resources.vm = env
+        .world
+        .resources
+        .vm
+        .as_ref()
+        .map(|vm| rune::Vm::new(vm.context().clone(), vm.unit().clone()));
+    // Lent to the simulation, instead of being cloned
+    sim.world.resources.data = std::mem::take(&mut env.world.resources.data);
+    commands::register_handlers(&mut sim.scheduler);
+
+    let mut kills = sim.scheduler.observe::<commands::Kill>();
+    let mut town_attacks = sim.scheduler.observe::<commands::AttackTown>();
+    let mut respawns = sim.scheduler.observe::<commands::RespawnPlayer>();
+
+    super::fight_start(&mut sim);
+    for _ in 0..MAX_PREVIEW_STEPS {
+        if sim.scheduler.step(&mut sim.world) {
+            continue;
+        }
+        if super::systems::handle_killed(&mut sim) {
+            continue;
+        }
+        if super::systems::check_loose(&sim) {
+            break;
+        }
+        if super::systems::handle_on_fight(&mut sim) {
+            continue;
+        }
+        if !super::npcs::next_attack(&mut sim) {
+            break;
+        }
+    }
+
+    let mut attackers = Vec::new();
+    while let Some(entity) = town_attacks.map_next(|c| c.0) {
+        attackers.push(entity);
+    }
+    // Kills minus respawns (eg. by a Necromancer or a Mime), so a unit
+    // brought back and killed again still counts as dead
+    let mut balance: Vec<(Entity, i32)> = Vec::new();
+    while let Some(entity) = kills.map_next(|c| c.0) {
+        // Npcs are removed after hitting the town
+        if attackers.contains(&entity) {
+            continue;
+        }
+        match balance.iter_mut().find(|(e, _)| *e == entity) {
+            Some((_, count)) => *count += 1,
+            None => balance.push((entity, 1)),
+        }
+    }
+    while let Some(entity) = respawns.map_next(|c| c.0) {
+        if let Some((_, count)) = balance.iter_mut().find(|(e, _)| *e == entity) {
+            *count -= 1;
+        }
+    }
+    let deaths = balance
+        .into_iter()
+        .filter(|(_, count)| *count > 0)
+        .map(|(e, _)| e)
+        .collect();
+
+    env.world.resources.data = std::mem::take(&mut sim.world.resources.data);
+
+    let before = &env.world.resources.player_data;
+    let after = &sim.world.resources.player_data;
+    BattlePreview {
+        deaths,
+        town_health: after.health as i32 - before.health as i32,
+        food: after.food as i32 - before.food as i32,
+    }
+}
@@ -0,0 +1,173 @@
+use wunderkammer::prelude::*;
+
+use game_data::{SynergyData, SynergyEffect};
+
+use crate::{commands::ChangeFood, GameEnv, World};
+
+/// Number of player units on the board carrying the tag.
+pub fn get_tag_count(world: &World, tag: &str) -> u32 {
+    world.get_players_with_tag(tag).len() as u32
+}
+
+/// Names of the synergies whose thresholds are met by the current board.
+pub fn get_reached_synergies(world: &World) -> Vec<String> {
+    let mut names = world
+        .resources
+        .data
+        .synergies
+        .iter()
+        .filter(|(_, s)| get_tag_count(world, &s.tag) >= s.count)
+        .map(|(n, _)| n.to_string())
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+/// Activates the reached synergies for the duration of the fight.
+pub(crate) fn synergies_start(env: &mut GameEnv) {
+    let active = get_reached_synergies(&env.world);
+    for name in active.iter() {
+        let Some(synergy) = env.world.resources.data.synergies.get(name) else {
+            continue;
+        };
+        if let SynergyEffect::Food(val) = synergy.effect {
+            env.scheduler.send(ChangeFood(val as i32, None));
+        }
+    }
+    env.world.resources.battle_state.synergies = active;
+}
+
+fn get_active(world: &World) -> impl Iterator<Item = &SynergyData> {
+    world
+        .resources
+        .battle_state
+        .synergies
+        .iter()
+        .filter_map(|n| world.resources.data.synergies.get(n))
+}
+
+pub(crate) fn get_heal_bonus(world: &World) -> u32 {
+    get_active(world)
+        .map(|s| match s.effect {
+            SynergyEffect::HealBonus(val) => val,
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Checks whether the unit shares a column with a unit locking it in place.
+pub(crate) fn is_column_locked(entity: Entity, world: &World) -> bool {
+    if world.components.player.get(entity).is_none() {
+        return false;
+    }
+    let Some(position) = world.components.position.get(entity) else {
+        return false;
+    };
+    get_active(world)
+        .filter(|s| s.effect == SynergyEffect::ColumnImmovable)
+        .any(|s| {
+            world.get_players_with_tag(&s.tag).iter().any(|e| {
+                world
+                    .components
+                    .position
+                    .get(e.into())
+                    .is_some_and(|p| p.x == position.x)
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        commands::{ChangeHealth, MoveUnit},
+        components::{Faction, Position},
+        test_utils::{place_unit, run_commands, test_env},
+    };
+
+    const UNITS: &str = r#"
+Healer:
+  sprite:
+    atlas: units
+  components:
+    health: [1, 4]
+    tags:
+      - Healer
+Heavy:
+  sprite:
+    atlas: units
+  components:
+    health: [3, 3]
+    tags:
+      - Heavy
+Peasant:
+  sprite:
+    atlas: units
+  components:
+    health: [1, 1]
+"#;
+
+    const SYNERGIES: &str = r#"
+Healing Circle:
+  tag: Healer
+  count: 3
+  effect: !heal_bonus 1
+Shield Wall:
+  tag: Heavy
+  count: 2
+  effect: column_immovable
+"#;
+
+    fn synergy_env() -> (GameEnv, ObservableQueue<crate::InputEvent>) {
+        let (mut env, queue) = test_env(&[("player", UNITS)]);
+        env.world.resources.data.add_synergies(SYNERGIES);
+        (env, queue)
+    }
+
+    #[test]
+    fn heal_bonus_is_added_after_fight_start() {
+        let (mut env, _queue) = synergy_env();
+        let healer = place_unit("Healer", Faction::Player, Position::new(0, 0), &mut env);
+        place_unit("Healer", Faction::Player, Position::new(1, 0), &mut env);
+        place_unit("Healer", Faction::Player, Position::new(2, 0), &mut env);
+
+        super::super::fight_start(&mut env);
+        run_commands(&mut env);
+        assert_eq!(
+            env.world.resources.battle_state.synergies,
+            vec!["Healing Circle".to_string()]
+        );
+
+        env.scheduler.send(ChangeHealth(healer, 1));
+        run_commands(&mut env);
+        assert_eq!(
+            env.world.components.health.get(healer).unwrap().current(),
+            3
+        );
+    }
+
+    #[test]
+    fn column_immovable_blocks_moves() {
+        let (mut env, _queue) = synergy_env();
+        place_unit("Heavy", Faction::Player, Position::new(0, 0), &mut env);
+        place_unit("Heavy", Faction::Player, Position::new(1, 0), &mut env);
+        let locked = place_unit("Peasant", Faction::Player, Position::new(0, 1), &mut env);
+        let free = place_unit("Peasant", Faction::Player, Position::new(3, 0), &mut env);
+
+        super::super::fight_start(&mut env);
+        run_commands(&mut env);
+
+        env.scheduler.send(MoveUnit(locked, Position::new(2, 1)));
+        env.scheduler.send(MoveUnit(free, Position::new(3, 1)));
+        run_commands(&mut env);
+
+        assert_eq!(
+            env.world.components.position.get(locked),
+            Some(&Position::new(0, 1))
+        );
+        assert_eq!(
+            env.world.components.position.get(free),
+            Some(&Position::new(3, 1))
+        );
+    }
+}
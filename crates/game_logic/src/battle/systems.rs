@@ -1,10 +1,10 @@
 use wunderkammer::prelude::*;
 
 use crate::{
-    commands::{check_trigger_limit, use_trigger_limit, RemoveUnit, RuneCommand},
+    commands::{run_triggers, RemoveUnit, RuneCommand},
     components::ValueDefault,
     globals,
-    scripting::run_command_script,
+    scripting::run_world_script,
     GameEnv, World,
 };
 
@@ -31,27 +31,57 @@ pub(crate) fn handle_on_fight(env: &mut GameEnv) -> bool {
         return false;
     };
 
-    if check_trigger_limit(entity, &env.world).is_err() {
-        return true;
+    crate::trace::clear_current(&mut env.world);
+    if let Some(commands) = run_triggers("on_fight", entity, &mut env.world, RuneCommand::None) {
+        for c in commands {
+            c.scheduler_send(&mut env.scheduler);
+        }
     }
 
-    let Some(script) = env.world.components.on_fight.get(entity) else {
-        return true;
-    };
-    let script = script.to_string();
+    true
+}
 
-    if let Some(commands) =
-        run_command_script(&script, entity.into(), &mut env.world, RuneCommand::None)
-    {
-        if !commands.is_empty() {
-            use_trigger_limit(entity, &mut env.world);
+/// Runs the trigger for all the board units.
+pub(crate) fn handle_board_trigger(trigger: &str, env: &mut GameEnv) {
+    crate::trace::clear_current(&mut env.world);
+    for entity in get_trigger_hosts(trigger, &env.world) {
+        if let Some(commands) = run_triggers(trigger, entity, &mut env.world, RuneCommand::None) {
+            for c in commands {
+                c.scheduler_send(&mut env.scheduler);
+            }
         }
+    }
+}
+
+/// Board units with the given trigger, in a consistent front to back order.
+pub(crate) fn get_trigger_hosts(trigger: &str, world: &World) -> Vec<Entity> {
+    let mut hosts = query_iter!(world, With(position, triggers))
+        .filter(|(_, _, t)| t.has(trigger))
+        .map(|(e, p, _)| (e, *p))
+        .collect::<Vec<_>>();
+
+    hosts.sort_by(|a, b| b.1.y.cmp(&a.1.y).then_with(|| a.1.x.cmp(&b.1.x)));
+    hosts.into_iter().map(|(e, _)| e).collect()
+}
+
+pub(crate) fn handle_village_passive(env: &mut GameEnv) {
+    let Some(passive) = env
+        .world
+        .resources
+        .player_data
+        .village
+        .as_ref()
+        .and_then(|v| env.world.resources.data.villages.get(v))
+        .and_then(|v| v.passive.clone())
+    else {
+        return;
+    };
+
+    if let Some(commands) = run_world_script(&passive, &mut env.world) {
         for c in commands {
             c.scheduler_send(&mut env.scheduler);
         }
     }
-
-    true
 }
 
 pub(crate) fn check_win(env: &GameEnv) -> bool {
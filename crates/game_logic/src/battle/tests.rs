@@ -0,0 +1,222 @@
+use wunderkammer::prelude::*;
+
+use crate::{
+    commands::{AttackTown, Kill, MoveUnit, SetState, Undo},
+    components::{Faction, Position},
+    globals::BOARD_H,
+    test_utils::{get_state, place_unit, run_commands, test_env},
+    trace::TraceKind,
+    GameEnv, InputEvent,
+};
+
+const PLAYERS: &str = r#"
+Watcher:
+  sprite:
+    atlas: units
+  components:
+    health: [3, 3]
+    triggers:
+      on_move: [watcher_move]
+      on_wave_start: [watcher_wave_start]
+      on_wave_end: [watcher_wave_end]
+      on_enemy_spawn: [watcher_enemy_spawn]
+      on_town_hit: [watcher_town_hit]
+      on_battle_end: [watcher_battle_end]
+  script: |
+    pub fn watcher_move(world, entity, cmd) {
+        let position = world.get("position", entity).unwrap();
+        RuneCommand::SetState(entity, "move_x", position.x)
+    }
+    pub fn watcher_wave_start(world, entity, cmd) {
+        RuneCommand::SetState(entity, "wave_start", world.get_wave())
+    }
+    pub fn watcher_wave_end(world, entity, cmd) {
+        RuneCommand::SetState(entity, "wave_end", world.get_wave())
+    }
+    pub fn watcher_enemy_spawn(world, entity, cmd) {
+        RuneCommand::SetState(entity, "enemy_spawn", 1)
+    }
+    pub fn watcher_town_hit(world, entity, cmd) {
+        RuneCommand::SetState(entity, "town_health", world.get_town_health())
+    }
+    pub fn watcher_battle_end(world, entity, cmd) {
+        RuneCommand::ChangeFood(1, None)
+    }
+"#;
+
+const NPCS: &str = r#"
+Lump:
+  sprite:
+    atlas: units
+  components:
+    health: [1, 1]
+    triggers:
+      on_spawn: [lump_spawn]
+      on_wave_start: [lump_wave_start]
+  script: |
+    pub fn lump_spawn(world, entity, cmd) {
+        RuneCommand::SetState(entity, "spawn", 1)
+    }
+    pub fn lump_wave_start(world, entity, cmd) {
+        RuneCommand::SetState(entity, "wave_start", world.get_wave())
+    }
+"#;
+
+fn battle_env() -> (GameEnv, ObservableQueue<InputEvent>) {
+    let (mut env, queue) = test_env(&[("player", PLAYERS), ("npcs", NPCS)]);
+    env.world.resources.player_data.level = 1;
+    env.world.resources.player_data.health = 10;
+    (env, queue)
+}
+
+fn settle(env: &mut GameEnv) {
+    for _ in 0..100 {
+        super::battle_update(env);
+    }
+}
+
+#[test]
+fn wave_triggers_wrap_the_wave_spawn() {
+    let (mut env, _queue) = battle_env();
+    let watcher = place_unit("Watcher", Faction::Player, Position::new(0, 0), &mut env);
+    env.world.resources.battle_state.wave = 1;
+
+    super::next_turn(&mut env);
+    settle(&mut env);
+
+    // Ended before the wave counter moved on
+    assert_eq!(get_state(watcher, "wave_end", &env), Some(1));
+    assert_eq!(get_state(watcher, "wave_start", &env), Some(2));
+
+    let npcs = query_iter!(env.world, With(npc, position))
+        .map(|(e, _, _)| e)
+        .collect::<Vec<_>>();
+    assert!(!npcs.is_empty());
+    for npc in npcs {
+        // Only fires, when the spawn has been applied
+        assert_eq!(get_state(npc, "wave_start", &env), Some(2));
+    }
+}
+
+#[test]
+fn on_move_sees_the_new_position() {
+    let (mut env, _queue) = battle_env();
+    let watcher = place_unit("Watcher", Faction::Player, Position::new(0, 0), &mut env);
+
+    env.scheduler.send(MoveUnit(watcher, Position::new(1, 0)));
+    run_commands(&mut env);
+
+    assert_eq!(get_state(watcher, "move_x", &env), Some(1));
+}
+
+#[test]
+fn on_enemy_spawn_runs_after_on_spawn() {
+    let (mut env, _queue) = battle_env();
+    let watcher = place_unit("Watcher", Faction::Player, Position::new(0, 0), &mut env);
+
+    let lump = place_unit(
+        "Lump",
+        Faction::Npc,
+        Position::new(0, BOARD_H as i32),
+        &mut env,
+    );
+
+    assert_eq!(get_state(lump, "spawn", &env), Some(1));
+    assert_eq!(get_state(watcher, "enemy_spawn", &env), Some(1));
+
+    let scripts = env
+        .world
+        .resources
+        .trace
+        .entries
+        .iter()
+        .filter(|e| e.kind == TraceKind::Script)
+        .map(|e| e.label.as_str())
+        .collect::<Vec<_>>();
+    let spawn = scripts
+        .iter()
+        .position(|l| l.starts_with("on_spawn: lump_spawn"));
+    let enemy_spawn = scripts
+        .iter()
+        .position(|l| l.starts_with("on_enemy_spawn: watcher_enemy_spawn"));
+    assert!(spawn.is_some() && enemy_spawn.is_some());
+    assert!(spawn < enemy_spawn);
+}
+
+#[test]
+fn on_town_hit_sees_the_damage() {
+    let (mut env, _queue) = battle_env();
+    let watcher = place_unit("Watcher", Faction::Player, Position::new(0, 0), &mut env);
+    let lump = place_unit(
+        "Lump",
+        Faction::Npc,
+        Position::new(0, BOARD_H as i32),
+        &mut env,
+    );
+
+    env.scheduler.send(AttackTown(lump));
+    run_commands(&mut env);
+
+    assert_eq!(get_state(watcher, "town_health", &env), Some(9));
+}
+
+#[test]
+fn on_battle_end_runs_before_the_board_is_cleared() {
+    let (mut env, _queue) = battle_env();
+    let watcher = place_unit("Watcher", Faction::Player, Position::new(0, 0), &mut env);
+
+    let food = env.world.resources.player_data.food;
+
+    super::battle_exit(&mut env);
+
+    assert_eq!(env.world.resources.player_data.food, food + 1);
+    assert!(env.world.components.position.get(watcher).is_none());
+}
+
+#[test]
+fn script_state_is_cleared_on_leaving_the_board() {
+    let (mut env, _queue) = battle_env();
+    let survivor = place_unit("Watcher", Faction::Player, Position::new(0, 0), &mut env);
+    let fallen = place_unit("Watcher", Faction::Player, Position::new(1, 0), &mut env);
+
+    env.scheduler
+        .send(SetState(survivor, "kills".to_string(), 2));
+    env.scheduler.send(SetState(fallen, "kills".to_string(), 2));
+    run_commands(&mut env);
+    assert_eq!(get_state(survivor, "kills", &env), Some(2));
+
+    env.scheduler.send(Kill(fallen));
+    run_commands(&mut env);
+    super::systems::handle_killed(&mut env);
+    run_commands(&mut env);
+    assert_eq!(get_state(fallen, "kills", &env), None);
+    // Kept while on the board
+    assert_eq!(get_state(survivor, "kills", &env), Some(2));
+
+    super::battle_exit(&mut env);
+    assert_eq!(get_state(survivor, "kills", &env), None);
+}
+
+#[test]
+fn undo_restores_the_board_and_keeps_the_data() {
+    let (mut env, _queue) = battle_env();
+    let watcher = place_unit("Watcher", Faction::Player, Position::new(0, 0), &mut env);
+
+    super::push_undo(&mut env);
+    // Snapshots do not hold the game data
+    assert!(env.world.resources.battle_state.undo_stack[0]
+        .resources
+        .data
+        .entities
+        .is_empty());
+
+    env.scheduler.send(MoveUnit(watcher, Position::new(1, 0)));
+    env.scheduler.send(Undo);
+    run_commands(&mut env);
+
+    assert_eq!(
+        env.world.components.position.get(watcher),
+        Some(&Position::new(0, 0))
+    );
+    assert!(env.world.resources.data.entities.contains_key("Watcher"));
+}
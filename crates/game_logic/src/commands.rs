@@ -2,33 +2,64 @@ use anyhow::Result;
 use rune::Any;
 use wunderkammer::prelude::*;
 
+use game_data::TagRule;
+
 use crate::{
-    battle::player::{remove_player_from_board, reset_player},
-    components::{Position, Tag},
+    battle::{
+        player::{remove_player_from_board, reset_player},
+        synergies::{get_heal_bonus, is_column_locked},
+    },
+    components::{get_faction, has_tag_rule, set_faction, Faction, Position},
     scripting::run_command_script,
-    utils::get_unit_at,
+    trace,
+    utils::{get_unit_at, is_on_board, is_on_extended_board, spawn_by_name},
     world::{Ent, World},
 };
 
 // Commands
 
+#[derive(Debug)]
 pub struct ChangeFood(pub i32, pub Option<Entity>);
+#[derive(Debug)]
 pub struct SummonPlayer(pub Entity, pub Position);
+#[derive(Debug)]
 pub struct SpawnUnit(pub Entity, pub Position);
+#[derive(Debug)]
 pub struct MoveUnit(pub Entity, pub Position);
+#[derive(Debug)]
 pub struct Attack(pub Entity, pub Entity);
+#[derive(Debug)]
 pub struct AttackTown(pub Entity);
+#[derive(Debug)]
 pub struct ChangeHealth(pub Entity, pub i32);
+#[derive(Debug)]
 pub struct Kill(pub Entity);
+#[derive(Debug)]
 pub struct RemoveUnit(pub Entity);
+#[derive(Debug)]
 pub struct RespawnPlayer(pub Entity, pub Position);
+#[derive(Debug)]
+pub struct ChangeTownHealth(pub i32);
+#[derive(Debug)]
+pub struct GainUnit(pub String);
+#[derive(Debug)]
+pub struct Transform(pub Entity, pub String);
+#[derive(Debug)]
+pub struct Summon(pub String, pub Position, pub Faction);
+#[derive(Debug)]
+pub struct SetState(pub Entity, pub String, pub i64);
+#[derive(Debug)]
+pub struct Undo;
 
 // Rune
 
-#[derive(Any, Clone, Copy, Debug)]
+#[derive(Any, Clone, Debug)]
 pub enum RuneCommand {
     #[rune(constructor)]
     None,
+    // Returned by the `on_before_*` scripts, to stop the pending command
+    #[rune(constructor)]
+    Cancel,
     #[rune(constructor)]
     SpawnUnit(#[rune(get)] Ent, #[rune(get)] Position),
     #[rune(constructor)]
@@ -45,20 +76,40 @@ pub enum RuneCommand {
     RemoveUnit(#[rune(get)] Ent),
     #[rune(constructor)]
     RespawnPlayer(#[rune(get)] Ent, #[rune(get)] Position),
+    #[rune(constructor)]
+    ChangeTownHealth(#[rune(get)] i32),
+    #[rune(constructor)]
+    GainUnit(String),
+    #[rune(constructor)]
+    Transform(#[rune(get)] Ent, String),
+    #[rune(constructor)]
+    Summon(String, #[rune(get)] Position, #[rune(get)] Faction),
+    #[rune(constructor)]
+    SetState(#[rune(get)] Ent, String, #[rune(get)] i64),
 }
 macro_rules! rune_send {
     { $( ($match_pat:pat => $cmd:expr) ),* } => {
         pub fn send(&self, cx: &mut SchedulerContext) {
             match self {
-                Self::None => (),
+                Self::None | Self::Cancel => (),
                 $(
                     $match_pat => cx.send($cmd),
                 )*
             }
         }
+        /// Same as the Debug output of the sent command.
+        pub fn label(&self) -> String {
+            match self {
+                Self::None => "None".to_string(),
+                Self::Cancel => "Cancel".to_string(),
+                $(
+                    $match_pat => format!("{:?}", $cmd),
+                )*
+            }
+        }
         pub fn scheduler_send(&self, scheduler: &mut Scheduler<World>) {
             match self {
-                Self::None => (),
+                Self::None | Self::Cancel => (),
                 $(
                     $match_pat => scheduler.send($cmd),
                 )*
@@ -75,87 +126,144 @@ impl RuneCommand {
             (Self::Attack(e, t) => Attack(e.into(), t.into())),
             (Self::Kill(e) => Kill(e.into())),
             (Self::RemoveUnit(e) => RemoveUnit(e.into())),
-            (Self::RespawnPlayer(e, p) => RespawnPlayer(e.into(), *p))
+            (Self::RespawnPlayer(e, p) => RespawnPlayer(e.into(), *p)),
+            (Self::ChangeTownHealth(v) => ChangeTownHealth(*v)),
+            (Self::GainUnit(n) => GainUnit(n.clone())),
+            (Self::Transform(e, n) => Transform(e.into(), n.clone())),
+            (Self::Summon(n, p, f) => Summon(n.clone(), *p, *f)),
+            (Self::SetState(e, k, v) => SetState(e.into(), k.clone(), *v))
     }
 }
 
 // Register
 
+macro_rules! traced {
+    // First handler of the command, starts a new trace entry
+    ($handler:ident, $cmd:ty) => {
+        traced!(@wrap $handler, $cmd, true)
+    };
+    // Later handlers, recorded under the entry of the first one
+    ($handler:ident, $cmd:ty, follow) => {
+        traced!(@wrap $handler, $cmd, false)
+    };
+    (@wrap $handler:ident, $cmd:ty, $begin:literal) => {{
+        fn traced(
+            cmd: &mut $cmd,
+            world: &mut World,
+            cx: &mut SchedulerContext,
+        ) -> Result<(), CommandError> {
+            if $begin {
+                trace::begin_dispatch(world, || format!("{:?}", cmd));
+            }
+            trace::begin_handler(world, stringify!($handler));
+            let result = $handler(cmd, world, cx);
+            if let Err(CommandError::Break) = result {
+                trace::reject_command(world, stringify!($handler));
+            }
+            result
+        }
+        traced
+    }};
+}
+
 pub(crate) fn register_handlers(scheduler: &mut Scheduler<World>) {
-    scheduler.add_system(change_food);
-    scheduler.add_system_with_priority(handle_on_ally_gain_food, 1);
-    scheduler.add_system(summon_player);
-    scheduler.add_system(spawn_unit);
-    scheduler.add_system_with_priority(handle_on_spawn, 1);
-    scheduler.add_system(move_unit);
-    scheduler.add_system(attack);
-    scheduler.add_system_with_priority(handle_on_attack, 1);
-    scheduler.add_system(attack_town);
-    scheduler.add_system(change_health);
-    scheduler.add_system_with_priority(handle_on_damage, 1);
-    scheduler.add_system_with_priority(handle_on_ally_heal, 2);
-    scheduler.add_system_with_priority(handle_on_ally_damage, 2);
-    scheduler.add_system(kill);
-    scheduler.add_system_with_priority(handle_on_kill, 1);
-    scheduler.add_system_with_priority(handle_on_ally_kill, 2);
-    scheduler.add_system(remove_unit);
-    scheduler.add_system(respawn_player);
+    scheduler.add_system(traced!(change_food, ChangeFood));
+    scheduler.add_system_with_priority(traced!(handle_on_ally_gain_food, ChangeFood, follow), 1);
+    scheduler.add_system(traced!(summon_player, SummonPlayer));
+    scheduler.add_system(traced!(spawn_unit, SpawnUnit));
+    scheduler.add_system_with_priority(traced!(handle_on_spawn, SpawnUnit, follow), 1);
+    scheduler.add_system_with_priority(traced!(handle_on_enemy_spawn, SpawnUnit, follow), 2);
+    scheduler.add_system(traced!(move_unit, MoveUnit));
+    scheduler.add_system_with_priority(traced!(handle_on_move, MoveUnit, follow), 1);
+    scheduler.add_system(traced!(attack, Attack));
+    scheduler.add_system_with_priority(traced!(handle_on_attack, Attack, follow), 1);
+    scheduler.add_system(traced!(attack_town, AttackTown));
+    scheduler.add_system_with_priority(traced!(handle_on_town_hit, AttackTown, follow), 1);
+    scheduler.add_system(traced!(change_health, ChangeHealth));
+    scheduler.add_system_with_priority(traced!(handle_on_damage, ChangeHealth, follow), 1);
+    scheduler.add_system_with_priority(traced!(handle_on_ally_heal, ChangeHealth, follow), 2);
+    scheduler.add_system_with_priority(traced!(handle_on_ally_damage, ChangeHealth, follow), 2);
+    scheduler.add_system_with_priority(traced!(handle_on_enemy_heal, ChangeHealth, follow), 2);
+    scheduler.add_system_with_priority(traced!(handle_on_enemy_damage, ChangeHealth, follow), 2);
+    scheduler.add_system(traced!(kill, Kill));
+    scheduler.add_system_with_priority(traced!(handle_on_kill, Kill, follow), 1);
+    scheduler.add_system_with_priority(traced!(handle_on_ally_kill, Kill, follow), 2);
+    scheduler.add_system_with_priority(traced!(handle_on_enemy_kill, Kill, follow), 2);
+    scheduler.add_system(traced!(remove_unit, RemoveUnit));
+    scheduler.add_system(traced!(respawn_player, RespawnPlayer));
+    scheduler.add_system(traced!(change_town_health, ChangeTownHealth));
+    scheduler.add_system(traced!(gain_unit, GainUnit));
+    scheduler.add_system(traced!(transform, Transform));
+    scheduler.add_system(traced!(summon, Summon));
+    scheduler.add_system(traced!(set_state, SetState));
+    scheduler.add_system(traced!(undo, Undo));
 }
 
 // Macros
 
-macro_rules! handle_on_ally {
-    ($world:ident, $cx:ident, $handler:ident, $target_entity:expr, $command:expr) => {{
-        if $world.components.player.get($target_entity).is_some() {
-            let hosts = query_iter!($world, With(player, position, $handler))
-                // Do not trigger on self
-                .filter(|(e, _, _, _)| *e != $target_entity)
-                .map(|(e, _, _, s)| (e, s.to_string()))
-                .collect::<Vec<_>>();
-
-            for (entity, script) in hosts {
-                if check_trigger_limit(entity, $world).is_err() {
-                    continue;
-                }
-                if let Some(commands) = run_command_script(&script, entity.into(), $world, $command)
-                {
-                    if !commands.is_empty() {
-                        use_trigger_limit(entity, $world);
-                    }
-                    for c in commands {
-                        c.send($cx);
-                    }
+// Sends a command from a handler, keeping track of its producer
+macro_rules! send {
+    ($world:ident, $cx:ident, $cmd:expr) => {{
+        let command = $cmd;
+        trace::record_send($world, || format!("{:?}", command));
+        $cx.send(command);
+    }};
+}
+
+macro_rules! handle_on_hosts {
+    ($world:ident, $cx:ident, $trigger:expr, $hosts:expr, $command:expr) => {{
+        let command = $command;
+        for entity in $hosts {
+            if let Some(commands) = run_triggers($trigger, entity, $world, command.clone()) {
+                for c in commands {
+                    c.send($cx);
                 }
             }
         }
     }};
 }
 
+macro_rules! handle_on_ally {
+    ($world:ident, $cx:ident, $trigger:expr, $target_entity:expr, $command:expr) => {{
+        let hosts = get_faction_hosts($trigger, $target_entity, true, $world);
+        handle_on_hosts!($world, $cx, $trigger, hosts, $command);
+    }};
+}
+
+macro_rules! handle_on_enemy {
+    ($world:ident, $cx:ident, $trigger:expr, $target_entity:expr, $command:expr) => {{
+        let hosts = get_faction_hosts($trigger, $target_entity, false, $world);
+        handle_on_hosts!($world, $cx, $trigger, hosts, $command);
+    }};
+}
+
 macro_rules! handle_on_self {
-    ($world:ident, $cx:ident, $handler:ident, $entity:expr, $command:expr) => {{
-        check_trigger_limit($entity, $world)?;
-
-        let component = $world
-            .components
-            .$handler
-            .get($entity)
-            .ok_or(CommandError::Continue)?
-            .clone();
-
-        if let Some(commands) = run_command_script(&component, $entity.into(), $world, $command) {
-            if !commands.is_empty() {
-                use_trigger_limit($entity, $world);
-            }
-            for c in commands {
-                c.send($cx);
-            }
+    ($world:ident, $cx:ident, $trigger:expr, $entity:expr, $command:expr) => {{
+        let commands =
+            run_triggers($trigger, $entity, $world, $command).ok_or(CommandError::Continue)?;
+        for c in commands {
+            c.send($cx);
         }
     }};
 }
 
 // Handlers
 
-fn change_food(cmd: &mut ChangeFood, world: &mut World) -> Result<(), CommandError> {
+fn change_food(
+    cmd: &mut ChangeFood,
+    world: &mut World,
+    cx: &mut SchedulerContext,
+) -> Result<(), CommandError> {
+    // Only intercept food yielded or taken by units, not the unit costs
+    if cmd.0 != 0 && cmd.1.is_some() {
+        let hosts = crate::battle::systems::get_trigger_hosts("on_before_food", world);
+        let command = RuneCommand::ChangeFood(cmd.0, cmd.1.map(|a| a.into()));
+        match intercept("on_before_food", hosts, command, world, cx) {
+            // A flipped sign (or zero) cancels the command
+            Some(RuneCommand::ChangeFood(v, _)) if v.signum() == cmd.0.signum() => cmd.0 = v,
+            _ => return Err(CommandError::Break),
+        }
+    }
     if cmd.0 < 0 {
         world.resources.player_data.food = world
             .resources
@@ -184,7 +292,7 @@ fn handle_on_ally_gain_food(
     handle_on_ally!(
         world,
         cx,
-        on_ally_gain_food,
+        "on_ally_gain_food",
         entity,
         RuneCommand::ChangeFood(cmd.0, cmd.1.map(|a| a.into()))
     );
@@ -217,13 +325,17 @@ fn summon_player(
 
     data.deck.retain(|a| *a != cmd.0);
 
-    cx.send(SpawnUnit(cmd.0, cmd.1));
-    cx.send(ChangeFood(-(cost as i32), None));
+    send!(world, cx, SpawnUnit(cmd.0, cmd.1));
+    send!(world, cx, ChangeFood(-(cost as i32), None));
 
     Ok(())
 }
 
-fn spawn_unit(cmd: &mut SpawnUnit, world: &mut World) -> Result<(), CommandError> {
+fn spawn_unit(
+    cmd: &mut SpawnUnit,
+    world: &mut World,
+    _: &mut SchedulerContext,
+) -> Result<(), CommandError> {
     if let Some(existing) = get_unit_at(world, cmd.1) {
         if world.components.killed.get(existing).is_none() && existing != cmd.0 {
             return Err(CommandError::Break);
@@ -241,7 +353,22 @@ fn handle_on_spawn(
     handle_on_self!(
         world,
         cx,
-        on_spawn,
+        "on_spawn",
+        cmd.0,
+        RuneCommand::SpawnUnit(cmd.0.into(), cmd.1)
+    );
+    Ok(())
+}
+
+fn handle_on_enemy_spawn(
+    cmd: &mut SpawnUnit,
+    world: &mut World,
+    cx: &mut SchedulerContext,
+) -> Result<(), CommandError> {
+    handle_on_enemy!(
+        world,
+        cx,
+        "on_enemy_spawn",
         cmd.0,
         RuneCommand::SpawnUnit(cmd.0.into(), cmd.1)
     );
@@ -253,10 +380,8 @@ fn move_unit(
     world: &mut World,
     _: &mut SchedulerContext,
 ) -> Result<(), CommandError> {
-    if let Some(tags) = world.components.tags.get(cmd.0) {
-        if tags.contains(&Tag::Heavy) {
-            return Err(CommandError::Break);
-        }
+    if has_tag_rule(cmd.0, TagRule::Immovable, world) || is_column_locked(cmd.0, world) {
+        return Err(CommandError::Break);
     }
     if get_unit_at(world, cmd.1).is_some() {
         return Err(CommandError::Break);
@@ -265,6 +390,21 @@ fn move_unit(
     Ok(())
 }
 
+fn handle_on_move(
+    cmd: &mut MoveUnit,
+    world: &mut World,
+    cx: &mut SchedulerContext,
+) -> Result<(), CommandError> {
+    handle_on_self!(
+        world,
+        cx,
+        "on_move",
+        cmd.0,
+        RuneCommand::MoveUnit(cmd.0.into(), cmd.1)
+    );
+    Ok(())
+}
+
 fn attack(
     cmd: &mut Attack,
     world: &mut World,
@@ -274,15 +414,17 @@ fn attack(
         .components
         .health
         .get(cmd.0)
-        .ok_or(CommandError::Break)?;
+        .ok_or(CommandError::Break)?
+        .current() as i32;
     let health_1 = world
         .components
         .health
         .get(cmd.1)
-        .ok_or(CommandError::Break)?;
+        .ok_or(CommandError::Break)?
+        .current() as i32;
 
-    cx.send(ChangeHealth(cmd.1, -(health_0.current() as i32)));
-    cx.send(ChangeHealth(cmd.0, -(health_1.current() as i32)));
+    send!(world, cx, ChangeHealth(cmd.1, -health_0));
+    send!(world, cx, ChangeHealth(cmd.0, -health_1));
     Ok(())
 }
 
@@ -294,7 +436,7 @@ fn handle_on_attack(
     handle_on_self!(
         world,
         cx,
-        on_attack,
+        "on_attack",
         cmd.0,
         RuneCommand::Attack(cmd.0.into(), cmd.1.into())
     );
@@ -319,7 +461,29 @@ fn attack_town(
         .saturating_sub(health.current());
 
     // npc is removed
-    cx.send(Kill(cmd.0));
+    send!(world, cx, Kill(cmd.0));
+    Ok(())
+}
+
+fn handle_on_town_hit(
+    cmd: &mut AttackTown,
+    world: &mut World,
+    cx: &mut SchedulerContext,
+) -> Result<(), CommandError> {
+    let damage = world
+        .components
+        .health
+        .get(cmd.0)
+        .ok_or(CommandError::Break)?
+        .current() as i32;
+    let hosts = crate::battle::systems::get_trigger_hosts("on_town_hit", world);
+    handle_on_hosts!(
+        world,
+        cx,
+        "on_town_hit",
+        hosts,
+        RuneCommand::ChangeTownHealth(-damage)
+    );
     Ok(())
 }
 
@@ -328,6 +492,23 @@ fn change_health(
     world: &mut World,
     cx: &mut SchedulerContext,
 ) -> Result<(), CommandError> {
+    if cmd.1 != 0 {
+        let trigger = if cmd.1 < 0 {
+            "on_before_damage"
+        } else {
+            "on_before_heal"
+        };
+        let command = RuneCommand::ChangeHealth(cmd.0.into(), cmd.1);
+        match intercept(trigger, vec![cmd.0], command, world, cx) {
+            // Damage can't be turned into a heal (or vice versa), zero cancels it
+            Some(RuneCommand::ChangeHealth(_, v)) if v.signum() == cmd.1.signum() => cmd.1 = v,
+            _ => return Err(CommandError::Break),
+        }
+    }
+
+    if cmd.1 > 0 && world.components.player.get(cmd.0).is_some() {
+        cmd.1 += get_heal_bonus(world) as i32;
+    }
     let health = world
         .components
         .health
@@ -337,7 +518,7 @@ fn change_health(
     if cmd.1 < 0 {
         health.sub((-cmd.1) as u32);
         if health.current() == 0 {
-            cx.send(Kill(cmd.0));
+            send!(world, cx, Kill(cmd.0));
         }
     } else {
         health.add(cmd.1 as u32);
@@ -370,7 +551,7 @@ fn handle_on_damage(
     handle_on_self!(
         world,
         cx,
-        on_damage,
+        "on_damage",
         cmd.0,
         RuneCommand::ChangeHealth(cmd.0.into(), cmd.1)
     );
@@ -391,7 +572,7 @@ fn handle_on_ally_heal(
     handle_on_ally!(
         world,
         cx,
-        on_ally_heal,
+        "on_ally_heal",
         cmd.0,
         RuneCommand::ChangeHealth(cmd.0.into(), cmd.1)
     );
@@ -424,7 +605,28 @@ fn handle_on_ally_damage(
     handle_on_ally!(
         world,
         cx,
-        on_ally_damage,
+        "on_ally_damage",
+        cmd.0,
+        RuneCommand::ChangeHealth(cmd.0.into(), cmd.1)
+    );
+
+    Ok(())
+}
+
+fn handle_on_enemy_heal(
+    cmd: &mut ChangeHealth,
+    world: &mut World,
+    cx: &mut SchedulerContext,
+) -> Result<(), CommandError> {
+    // Handle only heal
+    if cmd.1 <= 0 {
+        return Ok(());
+    }
+
+    handle_on_enemy!(
+        world,
+        cx,
+        "on_enemy_heal",
         cmd.0,
         RuneCommand::ChangeHealth(cmd.0.into(), cmd.1)
     );
@@ -432,7 +634,28 @@ fn handle_on_ally_damage(
     Ok(())
 }
 
-fn kill(cmd: &mut Kill, world: &mut World) -> Result<(), CommandError> {
+fn handle_on_enemy_damage(
+    cmd: &mut ChangeHealth,
+    world: &mut World,
+    cx: &mut SchedulerContext,
+) -> Result<(), CommandError> {
+    // Handle only damage
+    if cmd.1 >= 0 {
+        return Ok(());
+    }
+
+    handle_on_enemy!(
+        world,
+        cx,
+        "on_enemy_damage",
+        cmd.0,
+        RuneCommand::ChangeHealth(cmd.0.into(), cmd.1)
+    );
+
+    Ok(())
+}
+
+fn kill(cmd: &mut Kill, world: &mut World, _: &mut SchedulerContext) -> Result<(), CommandError> {
     world.components.killed.insert(cmd.0, ());
     Ok(())
 }
@@ -442,7 +665,7 @@ fn handle_on_kill(
     world: &mut World,
     cx: &mut SchedulerContext,
 ) -> Result<(), CommandError> {
-    handle_on_self!(world, cx, on_kill, cmd.0, RuneCommand::Kill(cmd.0.into()));
+    handle_on_self!(world, cx, "on_kill", cmd.0, RuneCommand::Kill(cmd.0.into()));
 
     Ok(())
 }
@@ -455,7 +678,7 @@ fn handle_on_ally_kill(
     handle_on_ally!(
         world,
         cx,
-        on_ally_kill,
+        "on_ally_kill",
         cmd.0,
         RuneCommand::Kill(cmd.0.into())
     );
@@ -463,8 +686,30 @@ fn handle_on_ally_kill(
     Ok(())
 }
 
-fn remove_unit(cmd: &mut RemoveUnit, world: &mut World) -> Result<(), CommandError> {
-    if world.components.player.get(cmd.0).is_some() {
+fn handle_on_enemy_kill(
+    cmd: &mut Kill,
+    world: &mut World,
+    cx: &mut SchedulerContext,
+) -> Result<(), CommandError> {
+    handle_on_enemy!(
+        world,
+        cx,
+        "on_enemy_kill",
+        cmd.0,
+        RuneCommand::Kill(cmd.0.into())
+    );
+
+    Ok(())
+}
+
+fn remove_unit(
+    cmd: &mut RemoveUnit,
+    world: &mut World,
+    _: &mut SchedulerContext,
+) -> Result<(), CommandError> {
+    if world.components.player.get(cmd.0).is_some()
+        && world.components.summoned.get(cmd.0).is_none()
+    {
         remove_player_from_board(cmd.0, world);
     } else {
         world.despawn(cmd.0);
@@ -482,13 +727,130 @@ fn respawn_player(
         return Err(CommandError::Break);
     }
     reset_player(cmd.0, world);
-    cx.send(SpawnUnit(cmd.0, cmd.1));
+    send!(world, cx, SpawnUnit(cmd.0, cmd.1));
+    Ok(())
+}
+
+fn change_town_health(
+    cmd: &mut ChangeTownHealth,
+    world: &mut World,
+    _: &mut SchedulerContext,
+) -> Result<(), CommandError> {
+    let data = &mut world.resources.player_data;
+    if cmd.0 < 0 {
+        data.health = data.health.saturating_sub((-cmd.0) as u32);
+    } else {
+        data.health = (data.health + cmd.0 as u32).min(data.max_health);
+    }
+    Ok(())
+}
+
+fn gain_unit(
+    cmd: &mut GainUnit,
+    world: &mut World,
+    _: &mut SchedulerContext,
+) -> Result<(), CommandError> {
+    let entity = spawn_by_name(&cmd.0, world).ok_or(CommandError::Break)?;
+    world.components.player.insert(entity, ());
+    world.resources.player_data.deck.push(entity);
+    Ok(())
+}
+
+fn transform(
+    cmd: &mut Transform,
+    world: &mut World,
+    cx: &mut SchedulerContext,
+) -> Result<(), CommandError> {
+    if let Some(&position) = world.components.position.get(cmd.0) {
+        return transform_on_board(cmd, position, world, cx);
+    }
+    let data = &world.resources.player_data;
+    if !data.deck.contains(&cmd.0) && !data.discard.contains(&cmd.0) {
+        return Err(CommandError::Break);
+    }
+
+    let entity = spawn_by_name(&cmd.1, world).ok_or(CommandError::Break)?;
+    world.components.player.insert(entity, ());
+
+    let data = &mut world.resources.player_data;
+    for e in data.deck.iter_mut().chain(data.discard.iter_mut()) {
+        if *e == cmd.0 {
+            *e = entity;
+        }
+    }
+    world.despawn(cmd.0);
+    Ok(())
+}
+
+fn transform_on_board(
+    cmd: &Transform,
+    position: Position,
+    world: &mut World,
+    cx: &mut SchedulerContext,
+) -> Result<(), CommandError> {
+    let faction = get_faction(cmd.0, world).ok_or(CommandError::Break)?;
+    let entity = spawn_by_name(&cmd.1, world).ok_or(CommandError::Break)?;
+    set_faction(entity, faction, world);
+    if world.components.summoned.get(cmd.0).is_some() {
+        world.components.summoned.insert(entity, ());
+    }
+
+    // Non summoned players keep the new form for the rest of the run
+    world.despawn(cmd.0);
+    send!(world, cx, SpawnUnit(entity, position));
+    Ok(())
+}
+
+fn summon(
+    cmd: &mut Summon,
+    world: &mut World,
+    cx: &mut SchedulerContext,
+) -> Result<(), CommandError> {
+    let on_board = match cmd.2 {
+        Faction::Player => is_on_board(cmd.1),
+        Faction::Npc => is_on_extended_board(cmd.1),
+    };
+    if !on_board || get_unit_at(world, cmd.1).is_some() {
+        return Err(CommandError::Break);
+    }
+
+    let entity = spawn_by_name(&cmd.0, world).ok_or(CommandError::Break)?;
+    set_faction(entity, cmd.2, world);
+    world.components.summoned.insert(entity, ());
+    send!(world, cx, SpawnUnit(entity, cmd.1));
+    Ok(())
+}
+
+fn set_state(
+    cmd: &mut SetState,
+    world: &mut World,
+    _: &mut SchedulerContext,
+) -> Result<(), CommandError> {
+    // Only named units can hold state
+    if world.components.name.get(cmd.0).is_none() {
+        return Err(CommandError::Break);
+    }
+    let mut state = world
+        .components
+        .script_state
+        .get(cmd.0)
+        .cloned()
+        .unwrap_or_default();
+    state.set(&cmd.1, cmd.2);
+    world.components.script_state.insert(cmd.0, state);
+    Ok(())
+}
+
+fn undo(_: &mut Undo, world: &mut World, _: &mut SchedulerContext) -> Result<(), CommandError> {
+    if !crate::battle::pop_undo(world) {
+        return Err(CommandError::Break);
+    }
     Ok(())
 }
 
 // Utils
 
-pub(crate) fn check_trigger_limit(entity: Entity, world: &World) -> Result<(), CommandError> {
+fn check_trigger_limit(entity: Entity, world: &World) -> Result<(), CommandError> {
     let Some(limit) = world.components.trigger_limit.get(entity) else {
         return Ok(());
     };
@@ -499,8 +861,281 @@ pub(crate) fn check_trigger_limit(entity: Entity, world: &World) -> Result<(), C
     }
 }
 
-pub(crate) fn use_trigger_limit(entity: Entity, world: &mut World) {
+/// Runs the interceptor scripts on a pending command, before it is applied.
+/// A returned command of the same kind and target replaces the pending one,
+/// `RuneCommand::Cancel` stops it, `RuneCommand::None` is ignored
+/// and any other commands are sent as usual.
+fn intercept(
+    trigger: &str,
+    hosts: Vec<Entity>,
+    command: RuneCommand,
+    world: &mut World,
+    cx: &mut SchedulerContext,
+) -> Option<RuneCommand> {
+    let mut command = command;
+    let mut cancelled = false;
+
+    for entity in hosts {
+        if cancelled {
+            break;
+        }
+        let Some(output) = run_triggers(trigger, entity, world, command.clone()) else {
+            continue;
+        };
+        for c in output {
+            match c {
+                RuneCommand::Cancel => cancelled = true,
+                RuneCommand::None => (),
+                c if is_same_target(&c, &command) => {
+                    trace::forget_pending(world, &c);
+                    command = c;
+                }
+                c => c.send(cx),
+            }
+        }
+    }
+
+    if cancelled {
+        return None;
+    }
+    Some(command)
+}
+
+fn is_same_target(a: &RuneCommand, b: &RuneCommand) -> bool {
+    match (a, b) {
+        (RuneCommand::ChangeHealth(e0, _), RuneCommand::ChangeHealth(e1, _)) => e0 == e1,
+        (RuneCommand::ChangeFood(_, e0), RuneCommand::ChangeFood(_, e1)) => e0 == e1,
+        _ => false,
+    }
+}
+
+/// Board units with the trigger, from the target's own (or the opposite) faction.
+fn get_faction_hosts(trigger: &str, target: Entity, allies: bool, world: &World) -> Vec<Entity> {
+    let Some(faction) = get_faction(target, world) else {
+        return Vec::new();
+    };
+    query_iter!(world, With(position, triggers))
+        // Do not trigger on self
+        .filter(|(e, _, t)| *e != target && t.has(trigger))
+        .filter(|(e, _, _)| get_faction(*e, world).is_some_and(|f| (f == faction) == allies))
+        .map(|(e, _, _)| e)
+        .collect()
+}
+
+/// Runs all the entity's scripts bound to the trigger kind.
+/// The trigger limit is used once, if any of the scripts produced commands
+/// (`RuneCommand::None` does not count).
+pub(crate) fn run_triggers(
+    trigger: &str,
+    entity: Entity,
+    world: &mut World,
+    command: RuneCommand,
+) -> Option<Vec<RuneCommand>> {
+    check_trigger_limit(entity, world).ok()?;
+    let scripts = world.components.triggers.get(entity)?.get(trigger).to_vec();
+    if scripts.is_empty() {
+        return None;
+    }
+
+    let mut commands = Vec::new();
+    for script in scripts {
+        if let Some(output) = run_command_script(&script, entity.into(), world, command.clone()) {
+            trace::record_script(world, trigger, &script, entity, &output);
+            commands.extend(output);
+        }
+    }
+    if commands.iter().any(|c| !matches!(c, RuneCommand::None)) {
+        use_trigger_limit(entity, world);
+    }
+    Some(commands)
+}
+
+fn use_trigger_limit(entity: Entity, world: &mut World) {
     if let Some(limit) = world.components.trigger_limit.get_mut(entity) {
         limit.sub(1);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{place_unit, run_commands, test_env};
+
+    const UNITS: &str = r#"
+Shield:
+  sprite:
+    atlas: units
+  components:
+    health: [5, 5]
+    triggers:
+      on_before_damage: [shield_before_damage]
+  script: |
+    pub fn shield_before_damage(world, entity, cmd) {
+        if let RuneCommand::ChangeHealth(target, val) = cmd {
+            return RuneCommand::ChangeHealth(target, val + 1);
+        }
+    }
+Barrier:
+  sprite:
+    atlas: units
+  components:
+    health: [5, 5]
+    triggers:
+      on_before_damage: [barrier_before_damage]
+  script: |
+    pub fn barrier_before_damage(world, entity, cmd) {
+        if let RuneCommand::ChangeHealth(target, val) = cmd {
+            return RuneCommand::ChangeHealth(target, val + 2);
+        }
+    }
+Wall:
+  sprite:
+    atlas: units
+  components:
+    health: [5, 5]
+    triggers:
+      on_before_damage: [wall_before_damage]
+  script: |
+    pub fn wall_before_damage(world, entity, cmd) {
+        RuneCommand::Cancel
+    }
+Idler:
+  sprite:
+    atlas: units
+  components:
+    health: [2, 4]
+    trigger_limit: [1, 1]
+    triggers:
+      on_before_heal: [idler_before_heal]
+  script: |
+    pub fn idler_before_heal(world, entity, cmd) {
+        RuneCommand::None
+    }
+Granary:
+  sprite:
+    atlas: units
+  components:
+    health: [1, 1]
+    triggers:
+      on_before_food: [granary_before_food]
+  script: |
+    pub fn granary_before_food(world, entity, cmd) {
+        if let RuneCommand::ChangeFood(val, source) = cmd {
+            return RuneCommand::ChangeFood(val * 2, source);
+        }
+    }
+"#;
+
+    fn health(entity: Entity, env: &crate::GameEnv) -> u32 {
+        env.world.components.health.get(entity).unwrap().current()
+    }
+
+    #[test]
+    fn on_before_damage_modifies_the_command() {
+        let (mut env, _queue) = test_env(&[("npcs", UNITS)]);
+        let shield = place_unit("Shield", Faction::Npc, Position::new(0, 0), &mut env);
+
+        env.scheduler.send(ChangeHealth(shield, -2));
+        run_commands(&mut env);
+
+        assert_eq!(health(shield, &env), 4);
+    }
+
+    #[test]
+    fn on_before_damage_does_not_turn_into_heal() {
+        let (mut env, _queue) = test_env(&[("npcs", UNITS)]);
+        let barrier = place_unit("Barrier", Faction::Npc, Position::new(0, 0), &mut env);
+
+        env.scheduler.send(ChangeHealth(barrier, -1));
+        run_commands(&mut env);
+
+        assert_eq!(health(barrier, &env), 5);
+    }
+
+    #[test]
+    fn on_before_damage_cancels_the_command() {
+        let (mut env, _queue) = test_env(&[("npcs", UNITS)]);
+        let wall = place_unit("Wall", Faction::Npc, Position::new(0, 0), &mut env);
+
+        env.scheduler.send(ChangeHealth(wall, -2));
+        run_commands(&mut env);
+
+        assert_eq!(health(wall, &env), 5);
+    }
+
+    #[test]
+    fn none_is_ignored_by_the_interceptor() {
+        let (mut env, _queue) = test_env(&[("npcs", UNITS)]);
+        let idler = place_unit("Idler", Faction::Npc, Position::new(0, 0), &mut env);
+
+        env.scheduler.send(ChangeHealth(idler, 1));
+        run_commands(&mut env);
+
+        assert_eq!(health(idler, &env), 3);
+        // Doing nothing does not use the trigger limit
+        let limit = env.world.components.trigger_limit.get(idler).unwrap();
+        assert_eq!(limit.current(), 1);
+    }
+
+    #[test]
+    fn summoned_player_is_removed_at_battle_end() {
+        let (mut env, _queue) = test_env(&[("player", UNITS)]);
+
+        env.scheduler.send(Summon(
+            "Granary".to_string(),
+            Position::new(1, 1),
+            Faction::Player,
+        ));
+        run_commands(&mut env);
+        let summoned = get_unit_at(&env.world, Position::new(1, 1)).unwrap();
+        assert!(env.world.components.summoned.get(summoned).is_some());
+        assert_eq!(get_faction(summoned, &env.world), Some(Faction::Player));
+
+        crate::battle::battle_exit(&mut env);
+
+        assert!(env.world.components.name.get(summoned).is_none());
+        assert!(!env.world.resources.player_data.discard.contains(&summoned));
+    }
+
+    #[test]
+    fn summon_on_occupied_tile_breaks() {
+        let (mut env, _queue) = test_env(&[("npcs", UNITS)]);
+        let shield = place_unit("Shield", Faction::Npc, Position::new(0, 0), &mut env);
+
+        env.scheduler.send(Summon(
+            "Wall".to_string(),
+            Position::new(0, 0),
+            Faction::Npc,
+        ));
+        run_commands(&mut env);
+
+        assert_eq!(get_unit_at(&env.world, Position::new(0, 0)), Some(shield));
+        assert!(query_iter!(env.world, With(name)).all(|(_, n)| n != "Wall"));
+    }
+
+    #[test]
+    fn transform_on_board_keeps_position_and_faction() {
+        let (mut env, _queue) = test_env(&[("npcs", UNITS)]);
+        let shield = place_unit("Shield", Faction::Npc, Position::new(2, 5), &mut env);
+
+        env.scheduler.send(Transform(shield, "Wall".to_string()));
+        run_commands(&mut env);
+
+        let wall = get_unit_at(&env.world, Position::new(2, 5)).unwrap();
+        assert_ne!(wall, shield);
+        assert_eq!(env.world.components.name.get(wall).unwrap(), "Wall");
+        assert_eq!(get_faction(wall, &env.world), Some(Faction::Npc));
+        assert!(env.world.components.name.get(shield).is_none());
+    }
+
+    #[test]
+    fn on_before_food_modifies_the_command() {
+        let (mut env, _queue) = test_env(&[("player", UNITS)]);
+        let granary = place_unit("Granary", Faction::Player, Position::new(0, 0), &mut env);
+
+        env.scheduler.send(ChangeFood(2, Some(granary)));
+        run_commands(&mut env);
+
+        assert_eq!(env.world.resources.player_data.food, 4);
+    }
+}
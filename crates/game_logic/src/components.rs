@@ -1,9 +1,12 @@
 use rune::{alloc::clone::TryClone, runtime::VmResult, Any};
 use serde::Deserialize;
-use std::ops::{Add, AddAssign, Sub, SubAssign};
+use std::{
+    collections::HashMap,
+    ops::{Add, AddAssign, Sub, SubAssign},
+};
 use wunderkammer::prelude::*;
 
-use game_data::EntityData;
+use game_data::{ability_key, EntityData, TagRule};
 
 use crate::World;
 
@@ -25,31 +28,84 @@ impl Tile {
     }
 }
 
-#[derive(Any, Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
-pub enum Tag {
-    #[rune(constructor)]
-    Basic,
+#[derive(Any, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Faction {
     #[rune(constructor)]
-    FoodProducer,
+    Player,
     #[rune(constructor)]
-    Healer,
-    #[rune(constructor)]
-    Heavy,
-}
-impl From<Tag> for String {
-    fn from(value: Tag) -> Self {
-        match value {
-            Tag::Basic => "Basic",
-            Tag::FoodProducer => "Food Producer",
-            Tag::Healer => "Healer",
-            Tag::Heavy => "Heavy",
-        }
-        .to_string()
+    Npc,
+}
+impl Faction {
+    #[rune::function(keep, instance, protocol = PARTIAL_EQ)]
+    pub fn partial_eq(&self, rhs: &Self) -> VmResult<bool> {
+        VmResult::Ok(self == rhs)
+    }
+}
+
+pub fn get_faction(entity: Entity, world: &World) -> Option<Faction> {
+    if world.0.components.player.get(entity).is_some() {
+        Some(Faction::Player)
+    } else if world.0.components.npc.get(entity).is_some() {
+        Some(Faction::Npc)
+    } else {
+        None
+    }
+}
+
+pub(crate) fn set_faction(entity: Entity, faction: Faction, world: &mut World) {
+    match faction {
+        Faction::Player => world.0.components.player.insert(entity, ()),
+        Faction::Npc => world.0.components.npc.insert(entity, ()),
+    };
+}
+
+/// Tags are defined in the data files (tags.yaml).
+#[derive(Any, Clone, Debug, Deserialize, PartialEq, Eq, Hash)]
+pub struct Tag(#[rune(get)] pub String);
+impl Tag {
+    pub fn name(&self) -> &str {
+        &self.0
     }
 }
-impl From<&Tag> for String {
-    fn from(value: &Tag) -> Self {
-        <Tag as Into<String>>::into(*value)
+
+/// Checks if any of the entity's tags carries the given rule.
+pub(crate) fn has_tag_rule(entity: Entity, rule: TagRule, world: &World) -> bool {
+    world.0.components.tags.get(entity).is_some_and(|tags| {
+        tags.iter()
+            .any(|t| world.0.resources.data.has_tag_rule(t.name(), rule))
+    })
+}
+
+/// Script functions (or ability keys) run for each trigger kind, eg. `on_fight`.
+/// Trigger kinds are plain strings, so new ones only have to be fired by the engine.
+#[derive(Any, Clone, Debug, Default, Deserialize)]
+pub struct Triggers(HashMap<String, Vec<String>>);
+impl Triggers {
+    pub fn get(&self, trigger: &str) -> &[String] {
+        self.0
+            .get(trigger)
+            .map(|a| a.as_slice())
+            .unwrap_or_default()
+    }
+    pub fn has(&self, trigger: &str) -> bool {
+        !self.get(trigger).is_empty()
+    }
+    pub fn push(&mut self, trigger: &str, script: String) {
+        self.0.entry(trigger.to_string()).or_default().push(script);
+    }
+}
+
+/// Key / value store, written by scripts through `RuneCommand::SetState`.
+/// Lasts for a single stay on the board - player units lose it when they die,
+/// respawn or leave the board at the battle end.
+#[derive(Any, Clone, Debug, Default, Deserialize)]
+pub struct ScriptState(HashMap<String, i64>);
+impl ScriptState {
+    pub fn get(&self, key: &str) -> Option<i64> {
+        self.0.get(key).copied()
+    }
+    pub fn set(&mut self, key: &str, value: i64) {
+        self.0.insert(key.to_string(), value);
     }
 }
 
@@ -84,10 +140,25 @@ impl ValueDefault {
     }
 }
 
-pub(crate) fn insert_components(entity: Entity, world: &mut World, data: &EntityData) {
+pub(crate) fn insert_components(entity: Entity, name: &str, world: &mut World, data: &EntityData) {
     for (k, v) in data.components.iter() {
         crate::world::Components::insert_from_yaml(entity, k, v, world);
     }
+    if data.abilities.is_empty() {
+        return;
+    }
+    // Abilities are registered next to the script functions
+    let mut triggers = world
+        .0
+        .components
+        .triggers
+        .get(entity)
+        .cloned()
+        .unwrap_or_default();
+    for (i, ability) in data.abilities.iter().enumerate() {
+        triggers.push(&ability.trigger, ability_key(name, i));
+    }
+    world.0.components.triggers.insert(entity, triggers);
 }
 
 pub(crate) const ORTHO: [Position; 4] = [
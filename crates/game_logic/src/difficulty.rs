@@ -0,0 +1,74 @@
+use crate::globals::SHOP_SIZE;
+
+/// Modifiers are unlocked one by one, each difficulty level
+/// includes all the modifiers of the previous ones.
+const MODIFIERS: [Modifier; 4] = [
+    Modifier::WaveScore,
+    Modifier::LessFood,
+    Modifier::NpcHealth,
+    Modifier::SmallShop,
+];
+pub const MAX_DIFFICULTY: u32 = MODIFIERS.len() as u32;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Modifier {
+    WaveScore,
+    LessFood,
+    NpcHealth,
+    SmallShop,
+}
+impl From<Modifier> for String {
+    fn from(value: Modifier) -> Self {
+        match value {
+            Modifier::WaveScore => "Larger waves",
+            Modifier::LessFood => "Less food",
+            Modifier::NpcHealth => "Tougher enemies",
+            Modifier::SmallShop => "Smaller shops",
+        }
+        .to_string()
+    }
+}
+impl From<&Modifier> for String {
+    fn from(value: &Modifier) -> Self {
+        <Modifier as Into<String>>::into(*value)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Difficulty(pub u32);
+impl Difficulty {
+    pub fn modifiers(&self) -> &'static [Modifier] {
+        &MODIFIERS[..(self.0.min(MAX_DIFFICULTY) as usize)]
+    }
+    pub fn has(&self, modifier: Modifier) -> bool {
+        self.modifiers().contains(&modifier)
+    }
+    pub fn wave_score_bonus(&self) -> u32 {
+        if self.has(Modifier::WaveScore) {
+            1
+        } else {
+            0
+        }
+    }
+    pub fn food_penalty(&self) -> u32 {
+        if self.has(Modifier::LessFood) {
+            1
+        } else {
+            0
+        }
+    }
+    pub fn npc_health_bonus(&self) -> u32 {
+        if self.has(Modifier::NpcHealth) {
+            1
+        } else {
+            0
+        }
+    }
+    pub fn shop_size(&self) -> usize {
+        if self.has(Modifier::SmallShop) {
+            SHOP_SIZE - 2
+        } else {
+            SHOP_SIZE
+        }
+    }
+}
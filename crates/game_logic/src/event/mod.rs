@@ -0,0 +1,67 @@
+use rand::prelude::*;
+
+use crate::{events::InputEvent, scripting::run_world_script, GameEnv};
+
+#[derive(Default)]
+pub struct EventState {
+    pub event: Option<String>,
+    pub done: bool,
+}
+
+pub fn event_init(state: &mut EventState, env: &mut GameEnv) {
+    let mut rng = thread_rng();
+    let mut pool = env
+        .world
+        .resources
+        .data
+        .events
+        .iter()
+        .map(|(n, e)| (n.to_string(), e.chance.unwrap_or(1.)))
+        .collect::<Vec<_>>();
+    // Keep the draw independent from the map ordering
+    pool.sort_by(|a, b| a.0.cmp(&b.0));
+
+    state.event = pool
+        .choose_weighted(&mut rng, |a| a.1)
+        .ok()
+        .map(|a| a.0.clone());
+    if state.event.is_none() {
+        state.done = true;
+    }
+}
+
+pub fn event_update(state: &mut EventState, env: &mut GameEnv) {
+    while let Some(event) = env.input.as_ref().unwrap().next() {
+        match event {
+            InputEvent::PickOption(i) => pick_option(i, state, env),
+            _ => (),
+        }
+    }
+}
+
+fn pick_option(i: usize, state: &mut EventState, env: &mut GameEnv) {
+    let Some(name) = &state.event else {
+        return;
+    };
+    let Some(option) = env
+        .world
+        .resources
+        .data
+        .events
+        .get(name)
+        .and_then(|e| e.options.get(i))
+    else {
+        return;
+    };
+
+    if let Some(outcome) = option.outcome.clone() {
+        if let Some(commands) = run_world_script(&outcome, &mut env.world) {
+            for c in commands {
+                c.scheduler_send(&mut env.scheduler);
+            }
+        }
+        // Outcomes are resolved immediately, there is nothing to animate
+        while env.scheduler.step(&mut env.world) {}
+    }
+    state.done = true;
+}
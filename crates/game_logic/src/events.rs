@@ -7,6 +7,9 @@ pub enum InputEvent {
     MoveUnit(Entity, Position),
     SummonPlayer(Entity, Position),
     Done,
+    Undo,
     PickUnit(usize),
+    PickNode(usize),
+    PickOption(usize),
     DiscardUnit(Entity),
 }
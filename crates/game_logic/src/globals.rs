@@ -6,3 +6,8 @@ pub const MAX_BATTLES: u32 = 12;
 
 pub const DECK_SIZE: usize = 8;
 pub const SHOP_SIZE: usize = 5;
+
+pub const MAP_W: usize = 4;
+pub const ELITE_MIN_ROW: usize = 3;
+pub const REST_MIN_ROW: usize = 2;
+pub const REST_HEAL: u32 = 2;
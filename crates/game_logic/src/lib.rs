@@ -1,22 +1,31 @@
+mod abilities;
 mod actions;
 pub mod battle;
 pub mod commands;
 pub mod components;
 pub mod deck;
+pub mod difficulty;
+pub mod event;
 mod events;
 pub mod globals;
+pub mod map;
 mod player;
+pub mod profile;
 pub mod scripting;
 pub mod shop;
 pub mod startup;
+#[cfg(test)]
+mod test_utils;
+pub mod trace;
 mod utils;
+pub mod village;
 mod world;
 
 pub use events::InputEvent;
-pub use utils::{get_tile_at, get_unit_at, is_on_board, is_on_extended_board};
+pub use utils::{can_place_unit, get_tile_at, get_unit_at, is_on_board, is_on_extended_board};
 pub use world::{GameEnv, World};
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub enum GameMode {
     #[default]
     Init,
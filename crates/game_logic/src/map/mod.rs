@@ -0,0 +1,219 @@
+use rand::prelude::*;
+
+use crate::{
+    events::InputEvent,
+    globals::{ELITE_MIN_ROW, MAP_W, MAX_BATTLES, REST_HEAL, REST_MIN_ROW},
+    GameEnv, World,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeKind {
+    Battle,
+    Elite,
+    Shop,
+    Rest,
+    Event,
+}
+impl NodeKind {
+    pub fn wave_score_bonus(&self) -> u32 {
+        match self {
+            Self::Elite => 2,
+            _ => 0,
+        }
+    }
+    pub fn shop_tier_bonus(&self) -> u32 {
+        match self {
+            Self::Elite => 2,
+            Self::Shop => 1,
+            _ => 0,
+        }
+    }
+}
+impl From<NodeKind> for String {
+    fn from(value: NodeKind) -> Self {
+        match value {
+            NodeKind::Battle => "Battle",
+            NodeKind::Elite => "Elite",
+            NodeKind::Shop => "Market",
+            NodeKind::Rest => "Rest",
+            NodeKind::Event => "Unknown",
+        }
+        .to_string()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MapNode {
+    pub kind: NodeKind,
+    // Vertical slot, in 0..MAP_W
+    pub column: usize,
+    // Indices of the reachable nodes in the following row
+    pub next: Vec<usize>,
+}
+
+#[derive(Clone, Default)]
+pub struct MapState {
+    pub rows: Vec<Vec<MapNode>>,
+    // (row, index) of the last visited node
+    pub current: Option<(usize, usize)>,
+}
+impl MapState {
+    pub fn current_node(&self) -> Option<&MapNode> {
+        let (row, idx) = self.current?;
+        self.rows.get(row)?.get(idx)
+    }
+    pub fn current_kind(&self) -> Option<NodeKind> {
+        self.current_node().map(|n| n.kind)
+    }
+    pub fn next_row(&self) -> usize {
+        self.current.map(|(r, _)| r + 1).unwrap_or(0)
+    }
+    pub fn is_reachable(&self, idx: usize) -> bool {
+        let Some(row) = self.rows.get(self.next_row()) else {
+            return false;
+        };
+        if idx >= row.len() {
+            return false;
+        }
+        match self.current_node() {
+            Some(node) => node.next.contains(&idx),
+            None => true,
+        }
+    }
+}
+
+pub(crate) fn map_game_init(world: &mut World) {
+    world.resources.map = generate_map();
+}
+
+/// Returns the kind of the picked node, when the player has made a choice.
+pub fn map_update(env: &mut GameEnv) -> Option<NodeKind> {
+    while let Some(event) = env.input.as_ref().unwrap().next() {
+        match event {
+            InputEvent::PickNode(i) => {
+                if let Some(kind) = pick_node(i, env) {
+                    return Some(kind);
+                }
+            }
+            _ => (),
+        }
+    }
+    None
+}
+
+fn pick_node(idx: usize, env: &mut GameEnv) -> Option<NodeKind> {
+    let map = &mut env.world.resources.map;
+    if !map.is_reachable(idx) {
+        return None;
+    }
+    let row = map.next_row();
+    map.current = Some((row, idx));
+
+    let kind = map.rows[row][idx].kind;
+
+    // Each map row is a single step of the run
+    env.world.resources.player_data.level += 1;
+
+    if kind == NodeKind::Rest {
+        let data = &mut env.world.resources.player_data;
+        data.health = (data.health + REST_HEAL).min(data.max_health);
+    }
+    Some(kind)
+}
+
+fn generate_map() -> MapState {
+    let mut rng = thread_rng();
+    let depth = MAX_BATTLES as usize;
+    let mut rows = Vec::new();
+
+    for row in 0..depth {
+        let count = match row {
+            0 => MAP_W - 1,
+            r if r == depth - 1 => 1,
+            _ => rng.gen_range(2..=MAP_W - 1),
+        };
+        let mut columns = (0..MAP_W).collect::<Vec<_>>();
+        columns.shuffle(&mut rng);
+        columns.truncate(count);
+        columns.sort();
+
+        let nodes = columns
+            .iter()
+            .map(|&column| MapNode {
+                kind: get_node_kind(row, depth, &mut rng),
+                column,
+                next: Vec::new(),
+            })
+            .collect();
+        rows.push(nodes);
+    }
+
+    for row in 0..depth - 1 {
+        connect_rows(&mut rows, row);
+    }
+
+    MapState {
+        rows,
+        current: None,
+    }
+}
+
+fn get_node_kind<R: Rng + ?Sized>(row: usize, depth: usize, rng: &mut R) -> NodeKind {
+    if row == 0 {
+        return NodeKind::Battle;
+    }
+    // Boss fight
+    if row == depth - 1 {
+        return NodeKind::Elite;
+    }
+    let mut pool = vec![
+        (NodeKind::Battle, 5.),
+        (NodeKind::Shop, 1.5),
+        (NodeKind::Event, 1.5),
+    ];
+    if row >= REST_MIN_ROW {
+        pool.push((NodeKind::Rest, 1.));
+    }
+    if row >= ELITE_MIN_ROW {
+        pool.push((NodeKind::Elite, 1.));
+    }
+    pool.choose_weighted(rng, |a| a.1).unwrap().0
+}
+
+fn connect_rows(rows: &mut [Vec<MapNode>], row: usize) {
+    let next_columns = rows[row + 1].iter().map(|n| n.column).collect::<Vec<_>>();
+    let mut has_incoming = vec![false; next_columns.len()];
+
+    for node in rows[row].iter_mut() {
+        for (i, &column) in next_columns.iter().enumerate() {
+            if column.abs_diff(node.column) <= 1 {
+                node.next.push(i);
+                has_incoming[i] = true;
+            }
+        }
+        // Every node has to lead somewhere
+        if node.next.is_empty() {
+            let i = closest(node.column, &next_columns);
+            node.next.push(i);
+            has_incoming[i] = true;
+        }
+    }
+
+    // Every node has to be reachable
+    let columns = rows[row].iter().map(|n| n.column).collect::<Vec<_>>();
+    for (i, incoming) in has_incoming.iter().enumerate() {
+        if !incoming {
+            let source = closest(next_columns[i], &columns);
+            rows[row][source].next.push(i);
+        }
+    }
+}
+
+fn closest(column: usize, columns: &[usize]) -> usize {
+    columns
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, c)| c.abs_diff(column))
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
@@ -2,23 +2,38 @@ use rand::prelude::*;
 use std::collections::VecDeque;
 use wunderkammer::prelude::*;
 
-use crate::{globals::DECK_SIZE, utils::take_random, World};
+use game_data::SquadSlot;
 
-#[derive(Default)]
+use crate::{globals::DECK_SIZE, World};
+
+#[derive(Clone, Default)]
 pub struct PlayerData {
     pub discard: Vec<Entity>,
     pub deck: Vec<Entity>,
     pub level: u32,
     pub health: u32,
+    pub max_health: u32,
     pub food: u32,
+    pub village: Option<String>,
 }
 
-pub(crate) fn player_game_init(world: &mut World) {
+pub(crate) fn player_game_init(world: &mut World, village: &str) {
+    let data = world
+        .0
+        .resources
+        .data
+        .villages
+        .get(village)
+        .expect("Unknown village!")
+        .clone();
+
     world.0.resources.player_data = PlayerData::default();
-    world.0.resources.player_data.health = 5;
+    world.0.resources.player_data.health = data.health;
+    world.0.resources.player_data.max_health = data.health;
+    world.0.resources.player_data.village = Some(village.to_string());
 
-    for name in get_initial_squad() {
-        let entity = crate::utils::spawn_by_name(name, world).unwrap();
+    for name in get_initial_squad(&data.units) {
+        let entity = crate::utils::spawn_by_name(&name, world).unwrap();
         world.0.components.player.insert(entity, ());
         world.0.resources.player_data.deck.push(entity);
     }
@@ -48,13 +63,13 @@ pub(crate) fn reset_deck(world: &mut World) {
 //     }
 // }
 
-fn get_initial_squad() -> Vec<&'static str> {
-    let mut output = vec!["Scarecrow"];
+fn get_initial_squad(slots: &[SquadSlot]) -> Vec<String> {
     let mut rng = thread_rng();
-    let mut special_units = vec!["Peasant", "Sheep"];
-    output.push(take_random(&mut special_units, &mut rng));
-    while output.len() < 4 {
-        output.push("Villager");
-    }
-    output
+    slots
+        .iter()
+        .filter_map(|s| match s {
+            SquadSlot::Unit(name) => Some(name.to_string()),
+            SquadSlot::Random(names) => names.choose(&mut rng).cloned(),
+        })
+        .collect()
 }
@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    difficulty::{Difficulty, MAX_DIFFICULTY},
+    GameMode, World,
+};
+
+/// Player's progress, persisted between runs.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Profile {
+    #[serde(default)]
+    pub unlocked_difficulty: u32,
+    #[serde(default)]
+    pub best_level: u32,
+    #[serde(default)]
+    pub runs: Vec<RunRecord>,
+    #[serde(default)]
+    pub units: BTreeMap<String, UnitRecord>,
+    #[serde(default)]
+    pub unlocked_units: BTreeSet<String>,
+}
+impl Profile {
+    pub fn from_yaml(s: &str) -> Self {
+        serde_yaml::from_str(s).unwrap_or_else(|e| {
+            log::error!("Can't parse profile: {}", e);
+            Self::default()
+        })
+    }
+    pub fn to_yaml(&self) -> String {
+        serde_yaml::to_string(self).expect("Can't serialize profile!")
+    }
+    pub fn is_unlocked(&self, difficulty: Difficulty) -> bool {
+        difficulty.0 <= self.unlocked_difficulty
+    }
+    pub fn unlock_next(&mut self, won: Difficulty) {
+        self.unlocked_difficulty = self.unlocked_difficulty.max(won.0 + 1).min(MAX_DIFFICULTY);
+    }
+    pub fn wins(&self) -> usize {
+        self.runs.iter().filter(|r| r.won).count()
+    }
+    /// Stores the result of a finished run and updates the unlocks.
+    pub fn record_run(&mut self, world: &World) {
+        let won = matches!(world.resources.game_mode, GameMode::Win);
+        let level = world.resources.player_data.level;
+
+        self.runs.push(RunRecord {
+            level,
+            difficulty: world.resources.difficulty.0,
+            won,
+        });
+        self.best_level = self.best_level.max(level);
+
+        // Count every unit once, even if present in multiple copies
+        let names = world
+            .resources
+            .player_data
+            .deck
+            .iter()
+            .chain(world.resources.player_data.discard.iter())
+            .filter_map(|e| world.components.name.get(*e))
+            .collect::<BTreeSet<_>>();
+        for name in names {
+            let record = self.units.entry(name.to_string()).or_default();
+            record.runs += 1;
+            if won {
+                record.wins += 1;
+            }
+        }
+
+        if won {
+            self.unlock_next(world.resources.difficulty);
+        }
+        self.update_unlocks(&world.resources.data);
+    }
+    fn update_unlocks(&mut self, data: &game_data::GameData) {
+        for (name, entity) in data.entities.iter() {
+            if entity.unlock.is_some_and(|l| l <= self.best_level) {
+                self.unlocked_units.insert(name.to_string());
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RunRecord {
+    pub level: u32,
+    pub difficulty: u32,
+    pub won: bool,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct UnitRecord {
+    pub runs: u32,
+    pub wins: u32,
+}
+impl UnitRecord {
+    pub fn win_rate(&self) -> f32 {
+        if self.runs == 0 {
+            return 0.;
+        }
+        self.wins as f32 / self.runs as f32
+    }
+}
@@ -1,13 +1,46 @@
 use anyhow::Result;
+use rand::prelude::*;
 use rune::{Diagnostics, Module, ToValue, Value, Vm};
-use std::sync::Arc;
+use std::{cell::RefCell, collections::HashSet, sync::Arc};
 
 use crate::{
     commands::RuneCommand,
-    components::{Position, Tag, Tile, ValueDefault},
+    components::{Faction, Position, Tag, Tile, ValueDefault},
     world::{Ent, World},
 };
 
+const PRELUDE: &str = "prelude";
+
+/// Seeded random generator available to the scripts.
+/// It is cloned with the world snapshots, so the battle preview rolls the same values.
+#[derive(Clone)]
+pub struct ScriptRng(RefCell<StdRng>);
+impl ScriptRng {
+    pub fn new(seed: u64) -> Self {
+        Self(RefCell::new(StdRng::seed_from_u64(seed)))
+    }
+    /// Inclusive range.
+    pub(crate) fn gen_range(&self, min: i64, max: i64) -> i64 {
+        if max <= min {
+            return min;
+        }
+        self.0.borrow_mut().gen_range(min..=max)
+    }
+    pub(crate) fn gen_bool(&self, p: f64) -> bool {
+        // NaN would make `gen_bool` panic
+        if !p.is_finite() {
+            return false;
+        }
+        self.0.borrow_mut().gen_bool(p.clamp(0., 1.))
+    }
+}
+impl Default for ScriptRng {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+/// Compiles all the data scripts. Fails on script errors (the diagnostics are printed to stderr).
 pub fn init_rune(world: &World) -> Result<Vm> {
     let mut context = rune_modules::default_context().unwrap();
 
@@ -20,6 +53,8 @@ pub fn init_rune(world: &World) -> Result<Vm> {
     command_module.ty::<Ent>()?;
     command_module.function_meta(Ent::clone)?;
     command_module.function_meta(Ent::partial_eq__meta)?;
+    command_module.ty::<Faction>()?;
+    command_module.function_meta(Faction::partial_eq__meta)?;
     command_module.ty::<Tag>()?;
     command_module.ty::<ValueDefault>()?;
     context.install(command_module)?;
@@ -29,11 +64,43 @@ pub fn init_rune(world: &World) -> Result<Vm> {
 
     let mut sources = rune::Sources::new();
 
+    // Shared helpers, available to all the scripts
+    match world.0.resources.data.scripts.get(PRELUDE) {
+        Some(script) => {
+            sources.insert(rune::Source::new(format!("{}.rn", PRELUDE), script)?)?;
+        }
+        None => log::error!("Script prelude is not loaded!"),
+    }
+
+    let mut script_files = HashSet::new();
     for (name, data) in world.0.resources.data.entities.iter() {
         if let Some(script) = &data.script {
             let entry = rune::Source::new(name, script)?;
             sources.insert(entry)?;
         }
+        if let Some(file) = &data.script_file {
+            script_files.insert(file);
+        }
+    }
+    for file in script_files {
+        let Some(script) = world.0.resources.data.scripts.get(file) else {
+            log::error!("Script file {} is not loaded!", file);
+            continue;
+        };
+        let entry = rune::Source::new(format!("{}.rn", file), script)?;
+        sources.insert(entry)?;
+    }
+    for (name, data) in world.0.resources.data.events.iter() {
+        if let Some(script) = &data.script {
+            let entry = rune::Source::new(name, script)?;
+            sources.insert(entry)?;
+        }
+    }
+    for (name, data) in world.0.resources.data.villages.iter() {
+        if let Some(script) = &data.script {
+            let entry = rune::Source::new(name, script)?;
+            sources.insert(entry)?;
+        }
     }
 
     let mut diagnostics = Diagnostics::new();
@@ -49,7 +116,7 @@ pub fn init_rune(world: &World) -> Result<Vm> {
         diagnostics.emit(&mut writer, &sources).unwrap();
     }
 
-    let unit = result.unwrap();
+    let unit = result.map_err(|e| anyhow::anyhow!("Script build failed: {}", e))?;
     let vm = Vm::new(Arc::new(context.runtime()?), Arc::new(unit));
     log::debug!("Rune VM created successfully");
 
@@ -62,11 +129,37 @@ pub(crate) fn run_command_script(
     world: &mut World,
     command: RuneCommand,
 ) -> Option<Vec<RuneCommand>> {
+    log::debug!("Running script: {}", script);
+    if let Some(ability) = world.0.resources.data.abilities.get(script) {
+        return Some(crate::abilities::run_ability(ability, entity.into(), world));
+    }
+
+    let mut vm = world.0.resources.vm.take().unwrap();
+
+    // Do not early exit here - it will result in a missing Vm
+    let result = parse_script_output(script, vm.call([script], (&*world, entity, command)));
+    log::debug!("{} result: {:?}", script, result);
+    world.0.resources.vm = Some(vm);
+    result
+}
+
+/// Runs a script that is not attached to any entity (eg. event outcomes).
+pub(crate) fn run_world_script(script: &str, world: &mut World) -> Option<Vec<RuneCommand>> {
     log::debug!("Running script: {}", script);
     let mut vm = world.0.resources.vm.take().unwrap();
 
-    let result = match vm.call([script], (&*world, entity, command)) {
-        // Do not early exit here - it will result in a missing Vm
+    // Do not early exit here - it will result in a missing Vm
+    let result = parse_script_output(script, vm.call([script], (&*world,)));
+    log::debug!("{} result: {:?}", script, result);
+    world.0.resources.vm = Some(vm);
+    result
+}
+
+fn parse_script_output(
+    script: &str,
+    output: Result<Value, rune::runtime::VmError>,
+) -> Option<Vec<RuneCommand>> {
+    match output {
         Ok(output) => {
             if let Ok(value) = rune::from_value(&output) {
                 // If already a vec
@@ -80,8 +173,175 @@ pub(crate) fn run_command_script(
             log::error!("Script {} failed: {}", script, e);
             None
         }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        commands::{ChangeHealth, Kill},
+        components::Faction,
+        test_utils::{place_unit, test_env},
+        GameEnv,
     };
-    log::debug!("{} result: {:?}", script, result);
-    world.0.resources.vm = Some(vm);
-    result
+
+    const UNITS: &str = r#"
+Healer:
+  sprite:
+    atlas: units
+  components:
+    health: [2, 4]
+    tags:
+      - Healer
+Peasant:
+  sprite:
+    atlas: units
+  components:
+    health: [2, 2]
+  script: |
+    pub fn test_heal_all(world, entity, _) {
+        heal_all(world, "Healer", 2)
+    }
+    pub fn test_damage_column(world, entity, _) {
+        damage_column(world, 1, 3)
+    }
+    pub fn test_random_player(world, entity, _) {
+        match random_player(world) {
+            Some(player) => RuneCommand::Kill(player),
+            None => RuneCommand::None,
+        }
+    }
+Poltergeist:
+  sprite:
+    atlas: units
+  components:
+    health: [1, 1]
+  script_file: poltergeist
+"#;
+
+    fn run(script: &str, env: &mut GameEnv) -> Vec<String> {
+        // The caller does not matter for the helpers
+        let caller = env.world.spawn();
+        let mut labels =
+            run_command_script(script, caller.into(), &mut env.world, RuneCommand::None)
+                .expect(script)
+                .iter()
+                .map(|c| c.label())
+                .collect::<Vec<_>>();
+        labels.sort();
+        labels
+    }
+
+    fn sorted(mut labels: Vec<String>) -> Vec<String> {
+        labels.sort();
+        labels
+    }
+
+    #[test]
+    fn gen_bool_rejects_invalid_chance() {
+        let rng = ScriptRng::new(0);
+        assert!(!rng.gen_bool(f64::NAN));
+        assert!(!rng.gen_bool(f64::INFINITY));
+        assert!(rng.gen_bool(2.));
+    }
+
+    #[test]
+    fn init_rune_returns_build_errors() {
+        let mut world = World::default();
+        world
+            .0
+            .resources
+            .data
+            .add_script(PRELUDE, "pub fn broken( {");
+        assert!(init_rune(&world).is_err());
+    }
+
+    #[test]
+    fn heal_all_targets_tagged_players() {
+        let (mut env, _queue) = test_env(&[("player", UNITS)]);
+        let a = place_unit("Healer", Faction::Player, Position::new(0, 0), &mut env);
+        let b = place_unit("Healer", Faction::Player, Position::new(2, 1), &mut env);
+        place_unit("Peasant", Faction::Player, Position::new(1, 0), &mut env);
+
+        let expected = sorted(vec![
+            format!("{:?}", ChangeHealth(a, 2)),
+            format!("{:?}", ChangeHealth(b, 2)),
+        ]);
+        assert_eq!(run("test_heal_all", &mut env), expected);
+    }
+
+    #[test]
+    fn damage_column_targets_the_column() {
+        let (mut env, _queue) = test_env(&[("player", UNITS)]);
+        let a = place_unit("Peasant", Faction::Player, Position::new(1, 0), &mut env);
+        let b = place_unit("Healer", Faction::Player, Position::new(1, 1), &mut env);
+        place_unit("Peasant", Faction::Player, Position::new(0, 0), &mut env);
+
+        let expected = sorted(vec![
+            format!("{:?}", ChangeHealth(a, -3)),
+            format!("{:?}", ChangeHealth(b, -3)),
+        ]);
+        assert_eq!(run("test_damage_column", &mut env), expected);
+    }
+
+    #[test]
+    fn helpers_on_empty_board() {
+        let (mut env, _queue) = test_env(&[("player", UNITS)]);
+
+        assert!(run("test_heal_all", &mut env).is_empty());
+        assert!(run("test_damage_column", &mut env).is_empty());
+        assert_eq!(
+            run("test_random_player", &mut env),
+            vec!["None".to_string()]
+        );
+    }
+
+    #[test]
+    fn random_player_follows_the_seed() {
+        let (mut env, _queue) = test_env(&[("player", UNITS)]);
+        let kills = [
+            place_unit("Peasant", Faction::Player, Position::new(0, 0), &mut env),
+            place_unit("Peasant", Faction::Player, Position::new(1, 1), &mut env),
+            place_unit("Healer", Faction::Player, Position::new(2, 0), &mut env),
+        ]
+        .map(|e| format!("{:?}", Kill(e)));
+
+        env.world.resources.script_rng = ScriptRng::new(42);
+        let first = run("test_random_player", &mut env);
+        env.world.resources.script_rng = ScriptRng::new(42);
+        let second = run("test_random_player", &mut env);
+
+        assert_eq!(first.len(), 1);
+        assert!(kills.contains(&first[0]));
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn script_files_use_the_prelude() {
+        let (mut env, _queue) = test_env(&[("npcs", UNITS)]);
+        env.world.resources.data.add_script(
+            "poltergeist",
+            include_str!("../../../assets/scripts/poltergeist.rn"),
+        );
+        env.world.resources.vm = Some(init_rune(&env.world).unwrap());
+
+        let poltergeist = place_unit("Poltergeist", Faction::Npc, Position::new(1, 4), &mut env);
+        let target = place_unit("Peasant", Faction::Player, Position::new(1, 1), &mut env);
+        let other = place_unit("Peasant", Faction::Player, Position::new(1, 0), &mut env);
+
+        let output = run_command_script(
+            "poltergeist_attack",
+            poltergeist.into(),
+            &mut env.world,
+            RuneCommand::Attack(poltergeist.into(), target.into()),
+        )
+        .unwrap()
+        .iter()
+        .map(|c| c.label())
+        .collect::<Vec<_>>();
+
+        // The attacked player is skipped
+        assert_eq!(output, vec![format!("{:?}", ChangeHealth(other, -1))]);
+    }
 }
@@ -10,7 +10,14 @@ pub struct ShopState {
 }
 
 pub fn shop_init(state: &mut ShopState, env: &mut GameEnv) {
-    let level = env.world.resources.player_data.level;
+    let level = env.world.resources.player_data.level
+        + env
+            .world
+            .resources
+            .map
+            .current_kind()
+            .map(|k| k.shop_tier_bonus())
+            .unwrap_or(0);
     // println!("Tier: {}", level);
     for (i, name) in get_choices(level, &env.world).iter().enumerate() {
         let Some(name) = name else {
@@ -53,6 +60,7 @@ fn get_choices(tier: u32, world: &World) -> [Option<String>; SHOP_SIZE] {
         .iter()
         .filter_map(|n| world.0.resources.data.entities.get(n).map(|e| (n, e)))
         .filter(|(_, e)| e.tier.unwrap_or(0) <= tier)
+        .filter(|(n, e)| e.unlock.is_none() || world.resources.unlocked_units.contains(*n))
         .map(|(n, e)| {
             let tier_dist = 0.8 / tier as f32 * e.tier.unwrap_or(1) as f32 + 0.2;
             // println!("T: {}, Et: {:?}, Dt: {}", tier, e.tier, tier_dist);
@@ -62,9 +70,10 @@ fn get_choices(tier: u32, world: &World) -> [Option<String>; SHOP_SIZE] {
 
     let mut output = [const { None }; SHOP_SIZE];
     let mut rng = thread_rng();
+    let size = world.resources.difficulty.shop_size();
 
     for (i, name) in filtered
-        .choose_multiple_weighted(&mut rng, SHOP_SIZE.min(filtered.len()), |a| a.0)
+        .choose_multiple_weighted(&mut rng, size.min(filtered.len()), |a| a.0)
         .unwrap()
         .map(|a| Some(a.1.to_string()))
         .enumerate()
@@ -6,5 +6,5 @@ pub fn init_game(env: &mut GameEnv) {
         crate::scripting::init_rune(&env.world).expect("Script engine initialization failed!"),
     );
     crate::commands::register_handlers(&mut env.scheduler);
-    crate::player::player_game_init(&mut env.world);
+    crate::map::map_game_init(&mut env.world);
 }
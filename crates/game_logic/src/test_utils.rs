@@ -0,0 +1,49 @@
+use wunderkammer::prelude::*;
+
+use crate::{
+    commands::{register_handlers, SpawnUnit},
+    components::{set_faction, Faction, Position},
+    scripting::init_rune,
+    utils::spawn_by_name,
+    GameEnv, InputEvent,
+};
+
+const PRELUDE: &str = include_str!("../../../assets/scripts/prelude.rn");
+
+/// Battle env with the prelude and the given `(category, yaml)` entity data.
+/// The returned queue keeps the input observer alive.
+pub(crate) fn test_env(data: &[(&str, &str)]) -> (GameEnv, ObservableQueue<InputEvent>) {
+    let mut env = GameEnv::default();
+    env.world.resources.data.add_script("prelude", PRELUDE);
+    for (category, yaml) in data {
+        env.world.resources.data.add_entities(yaml, category);
+    }
+    env.world.resources.vm = Some(init_rune(&env.world).expect("Test scripts do not compile!"));
+    register_handlers(&mut env.scheduler);
+
+    let queue = ObservableQueue::default();
+    env.input = Some(queue.subscribe());
+    (env, queue)
+}
+
+/// Spawns the unit and places it on the board, running the resulting commands.
+pub(crate) fn place_unit(
+    name: &str,
+    faction: Faction,
+    position: Position,
+    env: &mut GameEnv,
+) -> Entity {
+    let entity = spawn_by_name(name, &mut env.world).expect(name);
+    set_faction(entity, faction, &mut env.world);
+    env.scheduler.send(SpawnUnit(entity, position));
+    run_commands(env);
+    entity
+}
+
+pub(crate) fn run_commands(env: &mut GameEnv) {
+    while env.scheduler.step(&mut env.world) {}
+}
+
+pub(crate) fn get_state(entity: Entity, key: &str, env: &GameEnv) -> Option<i64> {
+    env.world.components.script_state.get(entity)?.get(key)
+}
@@ -0,0 +1,266 @@
+use std::collections::VecDeque;
+use wunderkammer::prelude::*;
+
+use crate::{commands::RuneCommand, World};
+
+const MAX_TRACE_LEN: usize = 64;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TraceKind {
+    Command,
+    Script,
+}
+
+#[derive(Clone, Debug)]
+pub struct TraceEntry {
+    pub id: usize,
+    pub parent: Option<usize>,
+    pub kind: TraceKind,
+    pub label: String,
+    // Rust handler that sent the command (script commands hang under the script entry)
+    pub sent_by: Option<&'static str>,
+    // Handler that rejected the command
+    pub rejected_by: Option<&'static str>,
+}
+
+// Command sent, but not dispatched yet
+#[derive(Clone)]
+struct Pending {
+    label: String,
+    parent: usize,
+    sent_by: Option<&'static str>,
+}
+
+/// Recent command cascades, recorded in debug builds only.
+/// Commands sent by scripts are linked to the script entry that produced them.
+#[derive(Clone, Default)]
+pub struct CommandTrace {
+    pub entries: VecDeque<TraceEntry>,
+    next_id: usize,
+    // Entry of the command currently passing through the handlers
+    current: Option<usize>,
+    // Handler currently running
+    handler: Option<&'static str>,
+    pending: VecDeque<Pending>,
+}
+impl CommandTrace {
+    pub fn get(&self, id: usize) -> Option<&TraceEntry> {
+        self.entries.iter().find(|e| e.id == id)
+    }
+    /// Number of ancestors still kept in the trace.
+    pub fn depth(&self, entry: &TraceEntry) -> usize {
+        let mut depth = 0;
+        let mut parent = entry.parent;
+        while let Some(e) = parent.and_then(|id| self.get(id)) {
+            depth += 1;
+            parent = e.parent;
+        }
+        depth
+    }
+    fn push(&mut self, parent: Option<usize>, kind: TraceKind, label: String) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.entries.push_back(TraceEntry {
+            id,
+            parent,
+            kind,
+            label,
+            sent_by: None,
+            rejected_by: None,
+        });
+        if self.entries.len() > MAX_TRACE_LEN {
+            self.entries.pop_front();
+        }
+        id
+    }
+    fn push_pending(&mut self, pending: Pending) {
+        self.pending.push_back(pending);
+        if self.pending.len() > MAX_TRACE_LEN {
+            self.pending.pop_front();
+        }
+    }
+}
+
+/// Called by the first (priority 0) handler of every command, so each dispatch
+/// gets its own entry - even when the later handlers see a modified command.
+pub(crate) fn begin_dispatch(world: &mut World, label: impl FnOnce() -> String) {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+    let label = label();
+    let trace = &mut world.resources.trace;
+    // Matched with the label of the command as sent, before any handler changed it
+    let pending = trace
+        .pending
+        .iter()
+        .position(|p| p.label == label)
+        .and_then(|i| trace.pending.remove(i));
+    let id = trace.push(
+        pending.as_ref().map(|p| p.parent),
+        TraceKind::Command,
+        label,
+    );
+    if let Some(entry) = trace.entries.back_mut() {
+        entry.sent_by = pending.and_then(|p| p.sent_by);
+    }
+    trace.current = Some(id);
+}
+
+/// Called by the traced wrapper before each handler.
+pub(crate) fn begin_handler(world: &mut World, handler: &'static str) {
+    world.resources.trace.handler = Some(handler);
+}
+
+/// Links a command sent by the running handler to the current entry.
+pub(crate) fn record_send(world: &mut World, label: impl FnOnce() -> String) {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+    let trace = &mut world.resources.trace;
+    let Some(parent) = trace.current else {
+        return;
+    };
+    let sent_by = trace.handler;
+    trace.push_pending(Pending {
+        label: label(),
+        parent,
+        sent_by,
+    });
+}
+
+/// Script output that replaced an intercepted command - it is never dispatched on its own.
+pub(crate) fn forget_pending(world: &mut World, command: &RuneCommand) {
+    let label = command.label();
+    let trace = &mut world.resources.trace;
+    if let Some(i) = trace.pending.iter().rposition(|p| p.label == label) {
+        trace.pending.remove(i);
+    }
+}
+
+pub(crate) fn reject_command(world: &mut World, handler: &'static str) {
+    let trace = &mut world.resources.trace;
+    let Some(current) = trace.current else {
+        return;
+    };
+    if let Some(entry) = trace.entries.iter_mut().find(|e| e.id == current) {
+        entry.rejected_by.get_or_insert(handler);
+    }
+}
+
+/// Scripts run outside of the command handlers (eg. on fight start) have no parent.
+pub(crate) fn clear_current(world: &mut World) {
+    world.resources.trace.current = None;
+}
+
+pub(crate) fn record_script(
+    world: &mut World,
+    trigger: &str,
+    script: &str,
+    host: Entity,
+    commands: &[RuneCommand],
+) {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+    let host_name = world.components.name.get(host).cloned().unwrap_or_default();
+    let trace = &mut world.resources.trace;
+    let id = trace.push(
+        trace.current,
+        TraceKind::Script,
+        format!("{}: {} ({})", trigger, script, host_name),
+    );
+    for c in commands {
+        // Never dispatched
+        if matches!(c, RuneCommand::None | RuneCommand::Cancel) {
+            continue;
+        }
+        trace.push_pending(Pending {
+            label: c.label(),
+            parent: id,
+            sent_by: None,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        commands::{Attack, ChangeHealth},
+        components::{Faction, Position},
+        test_utils::{place_unit, run_commands, test_env},
+    };
+
+    const UNITS: &str = r#"
+Stone:
+  sprite:
+    atlas: units
+  components:
+    health: [5, 5]
+    triggers:
+      on_damage: [stone_damage]
+  script: |
+    pub fn stone_damage(world, entity, cmd) {
+        RuneCommand::SetState(entity, "hit", 1)
+    }
+"#;
+
+    #[test]
+    fn identical_commands_get_own_entries() {
+        let (mut env, _queue) = test_env(&[("player", UNITS)]);
+        let stone = place_unit("Stone", Faction::Player, Position::new(0, 0), &mut env);
+        env.world.resources.trace = CommandTrace::default();
+
+        env.scheduler.send(ChangeHealth(stone, -1));
+        env.scheduler.send(ChangeHealth(stone, -1));
+        run_commands(&mut env);
+
+        let trace = &env.world.resources.trace;
+        let damage = trace
+            .entries
+            .iter()
+            .filter(|e| e.kind == TraceKind::Command && e.label.starts_with("ChangeHealth"))
+            .map(|e| e.id)
+            .collect::<Vec<_>>();
+        assert_eq!(damage.len(), 2);
+
+        // Each on_damage script hangs under its own dispatch
+        let parents = trace
+            .entries
+            .iter()
+            .filter(|e| e.kind == TraceKind::Script)
+            .map(|e| e.parent)
+            .collect::<Vec<_>>();
+        assert_eq!(parents, vec![Some(damage[0]), Some(damage[1])]);
+    }
+
+    #[test]
+    fn handler_commands_link_to_their_producer() {
+        let (mut env, _queue) = test_env(&[("player", UNITS)]);
+        let a = place_unit("Stone", Faction::Player, Position::new(0, 0), &mut env);
+        let b = place_unit("Stone", Faction::Npc, Position::new(0, 1), &mut env);
+        env.world.resources.trace = CommandTrace::default();
+
+        env.scheduler.send(Attack(b, a));
+        run_commands(&mut env);
+
+        let trace = &env.world.resources.trace;
+        let attack = trace
+            .entries
+            .iter()
+            .find(|e| e.label.starts_with("Attack"))
+            .unwrap();
+        let damage = trace
+            .entries
+            .iter()
+            .filter(|e| e.kind == TraceKind::Command && e.label.starts_with("ChangeHealth"))
+            .collect::<Vec<_>>();
+        assert_eq!(damage.len(), 2);
+        for e in damage {
+            assert_eq!(e.parent, Some(attack.id));
+            assert_eq!(e.sent_by, Some("attack"));
+        }
+        // Everything sent has been dispatched
+        assert!(trace.pending.is_empty());
+    }
+}
@@ -1,8 +1,11 @@
 use rand::prelude::*;
 use wunderkammer::prelude::*;
 
+use game_data::TagRule;
+
 use crate::{
-    components::Position,
+    battle::synergies::is_column_locked,
+    components::{has_tag_rule, Position},
     globals::{BOARD_H, BOARD_W, MAX_WAVE_H},
     World,
 };
@@ -18,7 +21,7 @@ pub fn is_on_extended_board(p: Position) -> bool {
 pub(crate) fn spawn_by_name(name: &str, world: &mut World) -> Option<Entity> {
     let entity = world.0.spawn();
     let data = world.0.resources.data.entities.get(name)?.clone();
-    crate::components::insert_components(entity, world, &data);
+    crate::components::insert_components(entity, name, world, &data);
     world.0.components.name.insert(entity, name.to_string());
     Some(entity)
 }
@@ -30,6 +33,25 @@ pub fn get_unit_at(world: &World, position: Position) -> Option<Entity> {
         .next()
 }
 
+/// Checks whether a hand or board unit can be placed at the given tile.
+pub fn can_place_unit(world: &World, entity: Entity, position: Position) -> bool {
+    if !is_on_board(position) || get_unit_at(world, position).is_some() {
+        return false;
+    }
+    let data = &world.0.resources.player_data;
+    if data.deck.contains(&entity) {
+        return world
+            .0
+            .components
+            .cost
+            .get(entity)
+            .is_some_and(|c| *c <= data.food);
+    }
+    world.0.components.position.get(entity).is_some()
+        && !has_tag_rule(entity, TagRule::Immovable, world)
+        && !is_column_locked(entity, world)
+}
+
 pub fn get_tile_at(world: &World, position: Position) -> Option<Entity> {
     query_iter!(world.0, With(tile, position))
         .filter(|(_, _, p)| **p == position)
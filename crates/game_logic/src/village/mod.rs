@@ -0,0 +1,37 @@
+use crate::{events::InputEvent, GameEnv};
+
+#[derive(Default)]
+pub struct VillageState {
+    pub choices: Vec<String>,
+    pub done: bool,
+}
+
+pub fn village_init(state: &mut VillageState, env: &mut GameEnv) {
+    let mut choices = env
+        .world
+        .resources
+        .data
+        .villages
+        .keys()
+        .cloned()
+        .collect::<Vec<_>>();
+    choices.sort();
+    state.choices = choices;
+}
+
+pub fn village_update(state: &mut VillageState, env: &mut GameEnv) {
+    while let Some(event) = env.input.as_ref().unwrap().next() {
+        match event {
+            InputEvent::PickOption(i) => pick_village(i, state, env),
+            _ => (),
+        }
+    }
+}
+
+fn pick_village(i: usize, state: &mut VillageState, env: &mut GameEnv) {
+    let Some(name) = state.choices.get(i) else {
+        return;
+    };
+    crate::player::player_game_init(&mut env.world, name);
+    state.done = true;
+}
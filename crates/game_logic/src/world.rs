@@ -16,7 +16,7 @@ pub struct GameEnv {
     pub input: Option<Observer<crate::InputEvent>>,
 }
 
-type WorldInner = WorldStorage<Components, Resources>;
+pub(crate) type WorldInner = WorldStorage<Components, Resources>;
 
 #[derive(Any, Default)]
 pub struct World(pub WorldInner);
@@ -32,6 +32,84 @@ impl World {
         (BOARD_W, BOARD_H)
     }
 
+    #[rune::function]
+    fn get_deck(&self) -> Vec<Ent> {
+        self.resources
+            .player_data
+            .deck
+            .iter()
+            .map(|e| e.into())
+            .collect()
+    }
+
+    #[rune::function]
+    fn get_discard(&self) -> Vec<Ent> {
+        self.resources
+            .player_data
+            .discard
+            .iter()
+            .map(|e| e.into())
+            .collect()
+    }
+
+    #[rune::function]
+    fn get_name(&self, entity: &Ent) -> Option<String> {
+        self.0.components.name.get(entity.into()).cloned()
+    }
+
+    #[rune::function]
+    fn get_town_health(&self) -> u32 {
+        self.resources.player_data.health
+    }
+
+    #[rune::function]
+    fn get_max_town_health(&self) -> u32 {
+        self.resources.player_data.max_health
+    }
+
+    #[rune::function]
+    fn get_level(&self) -> u32 {
+        self.resources.player_data.level
+    }
+
+    #[rune::function]
+    fn get_wave(&self) -> u32 {
+        self.resources.battle_state.wave
+    }
+
+    /// Npcs still on the board.
+    #[rune::function]
+    fn get_npc_count(&self) -> usize {
+        query_iter!(self.0, With(npc, position))
+            .filter(|(e, _, _)| self.0.components.killed.get(*e).is_none())
+            .count()
+    }
+
+    /// Random integer from the inclusive range.
+    #[rune::function]
+    fn random_range(&self, min: i64, max: i64) -> i64 {
+        self.resources.script_rng.gen_range(min, max)
+    }
+
+    #[rune::function]
+    fn random_chance(&self, p: f64) -> bool {
+        self.resources.script_rng.gen_bool(p)
+    }
+
+    #[rune::function]
+    fn get_active_synergies(&self) -> Vec<String> {
+        self.resources.battle_state.synergies.clone()
+    }
+
+    #[rune::function]
+    fn has_synergy(&self, name: &str) -> bool {
+        self.resources
+            .battle_state
+            .synergies
+            .iter()
+            .any(|a| a == name)
+    }
+
     // Components
     #[rune::function]
     fn query(&self, with: Vec<String>, without: Vec<String>) -> Vec<Ent> {
@@ -65,8 +143,8 @@ impl World {
         Some(get_unit_at(self, *position)?.into())
     }
 
-    #[rune::function]
-    fn get_player_in_front(&self, entity: &Ent) -> Option<Ent> {
+    #[rune::function(keep)]
+    pub(crate) fn get_player_in_front(&self, entity: &Ent) -> Option<Ent> {
         let mut position = *self.0.components.position.get(entity.into())?;
         position.y += 1;
         get_unit_at(self, position)
@@ -74,8 +152,8 @@ impl World {
             .map(|a| a.into())
     }
 
-    #[rune::function]
-    fn get_adjacent_players(&self, entity: &Ent) -> Vec<Ent> {
+    #[rune::function(keep)]
+    pub(crate) fn get_adjacent_players(&self, entity: &Ent) -> Vec<Ent> {
         let Some(position) = self.0.components.position.get(entity.into()) else {
             return Vec::new();
         };
@@ -88,22 +166,32 @@ impl World {
             .collect()
     }
 
-    #[rune::function]
-    fn get_players_in_column(&self, x: i32) -> Vec<Ent> {
+    #[rune::function(keep)]
+    pub(crate) fn get_players_in_column(&self, x: i32) -> Vec<Ent> {
         query_iter!(self.0, With(player, position))
             .filter(|(_, _, p)| p.x == x)
             .map(|(e, _, _)| e.into())
             .collect()
     }
 
-    #[rune::function]
-    fn get_players_with_tag(&self, tag: &Tag) -> Vec<Ent> {
+    #[rune::function(keep)]
+    pub(crate) fn get_players_with_tag(&self, tag: &str) -> Vec<Ent> {
         query_iter!(self.0, With(player, position, tags))
-            .filter(|(_, _, _, t)| t.contains(tag))
+            .filter(|(_, _, _, t)| t.iter().any(|a| a.name() == tag))
             .map(|(e, _, _, _)| e.into())
             .collect()
     }
 
+    #[rune::function]
+    fn get_faction(&self, entity: &Ent) -> Option<Faction> {
+        get_faction(entity.into(), self)
+    }
+
+    #[rune::function]
+    fn get_state(&self, entity: &Ent, key: &str) -> Option<i64> {
+        self.0.components.script_state.get(entity.into())?.get(key)
+    }
+
     #[rune::function]
     fn is_in_front(&self, entity: &Ent, other: &Ent) -> bool {
         match (
@@ -138,7 +226,7 @@ impl std::ops::DerefMut for World {
     }
 }
 
-#[derive(Any, Default, ComponentSet, RuneAdapter, ComponentGen)]
+#[derive(Any, Clone, Default, ComponentSet, RuneAdapter, ComponentGen)]
 pub struct Components {
     pub cost: ComponentStorage<u32>,
     pub health: ComponentStorage<ValueDefault>,
@@ -146,33 +234,48 @@ pub struct Components {
     pub killed: ComponentStorage<()>,
     pub name: ComponentStorage<String>,
     pub npc: ComponentStorage<()>,
-    // handlers start
-    pub on_spawn: ComponentStorage<String>,
-    pub on_fight: ComponentStorage<String>,
-    pub on_kill: ComponentStorage<String>,
-    pub on_ally_kill: ComponentStorage<String>,
-    pub on_attack: ComponentStorage<String>,
-    pub on_damage: ComponentStorage<String>,
-    pub on_ally_heal: ComponentStorage<String>,
-    pub on_ally_damage: ComponentStorage<String>,
-    pub on_ally_gain_food: ComponentStorage<String>,
-    // handlers end
     pub player: ComponentStorage<()>,
     pub position: ComponentStorage<Position>,
+    pub script_state: ComponentStorage<ScriptState>,
+    // Created by scripts, removed at the battle end
+    pub summoned: ComponentStorage<()>,
     pub tags: ComponentStorage<Vec<Tag>>,
     pub tile: ComponentStorage<Tile>,
     pub trigger_limit: ComponentStorage<ValueDefault>,
+    pub triggers: ComponentStorage<Triggers>,
 }
 
 #[derive(Default)]
 pub struct Resources {
     pub battle_state: crate::battle::BattleState,
     pub data: game_data::GameData,
+    pub difficulty: crate::difficulty::Difficulty,
     pub game_mode: crate::GameMode,
+    pub map: crate::map::MapState,
     pub player_data: PlayerData,
+    pub script_rng: crate::scripting::ScriptRng,
+    pub trace: crate::trace::CommandTrace,
+    pub unlocked_units: std::collections::BTreeSet<String>,
     // serialize as none
     pub vm: Option<rune::Vm>,
 }
+impl Clone for Resources {
+    // Used for world snapshots - the Vm is not cloned.
+    fn clone(&self) -> Self {
+        Self {
+            battle_state: self.battle_state.clone(),
+            data: self.data.clone(),
+            difficulty: self.difficulty,
+            game_mode: self.game_mode.clone(),
+            map: self.map.clone(),
+            player_data: self.player_data.clone(),
+            script_rng: self.script_rng.clone(),
+            trace: self.trace.clone(),
+            unlocked_units: self.unlocked_units.clone(),
+            vm: None,
+        }
+    }
+}
 
 #[derive(Any, Clone, Copy, Debug, PartialEq, Eq, TryClone)]
 pub struct Ent(u16, u16);
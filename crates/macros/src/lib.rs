@@ -32,15 +32,29 @@ fn impl_rune_adapter(ast: &syn::DeriveInput) -> TokenStream {
                 module.function_meta(World::get)?;
                 module.function_meta(World::get_unit_at)?;
                 module.function_meta(World::get_tile_at)?;
-                module.function_meta(World::get_player_in_front)?;
-                module.function_meta(World::get_adjacent_players)?;
-                module.function_meta(World::get_players_in_column)?;
-                module.function_meta(World::get_players_with_tag)?;
+                module.function_meta(World::get_player_in_front__meta)?;
+                module.function_meta(World::get_adjacent_players__meta)?;
+                module.function_meta(World::get_players_in_column__meta)?;
+                module.function_meta(World::get_players_with_tag__meta)?;
+                module.function_meta(World::get_faction)?;
+                module.function_meta(World::get_state)?;
                 module.function_meta(World::is_in_front)?;
                 module.function_meta(World::is_adjacent)?;
                 module.function_meta(World::query)?;
                 module.function_meta(World::get_current_food)?;
                 module.function_meta(World::board_size)?;
+                module.function_meta(World::get_deck)?;
+                module.function_meta(World::get_discard)?;
+                module.function_meta(World::get_name)?;
+                module.function_meta(World::get_town_health)?;
+                module.function_meta(World::get_max_town_health)?;
+                module.function_meta(World::get_level)?;
+                module.function_meta(World::get_wave)?;
+                module.function_meta(World::get_npc_count)?;
+                module.function_meta(World::random_range)?;
+                module.function_meta(World::random_chance)?;
+                module.function_meta(World::get_active_synergies)?;
+                module.function_meta(World::has_synergy)?;
                 Ok(module)
             }
         }